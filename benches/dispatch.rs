@@ -0,0 +1,149 @@
+//! Compares dispatch through generated enum with the `enum_dispatch` crate, `Box<dyn Trait>` and a hand-written `match`
+//!
+//! Run with `cargo bench`, every case calls `area()` on the same 5-variant collection in a tight loop
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f64;
+    }
+
+    pub enum Shape {
+        Square(Square),
+        Rect(Rect),
+        Circle(Circle),
+        Triangle(Triangle),
+        Ring(Ring),
+    }
+);
+
+pub struct Square(f64);
+pub struct Rect(f64, f64);
+pub struct Circle(f64);
+pub struct Triangle(f64, f64);
+pub struct Ring(f64, f64);
+
+impl Area for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+impl Area for Rect {
+    fn area(&self) -> f64 {
+        self.0 * self.1
+    }
+}
+
+impl Area for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.0 * self.0
+    }
+}
+
+impl Area for Triangle {
+    fn area(&self) -> f64 {
+        self.0 * self.1 / 2.0
+    }
+}
+
+impl Area for Ring {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * (self.1 * self.1 - self.0 * self.0)
+    }
+}
+
+// same variant types dispatched by the `enum_dispatch` crate, its attribute needs the trait declared outside of our macro
+#[enum_dispatch::enum_dispatch]
+pub trait ProcArea {
+    fn proc_area(&self) -> f64;
+}
+
+impl<T: Area> ProcArea for T {
+    #[inline]
+    fn proc_area(&self) -> f64 {
+        self.area()
+    }
+}
+
+#[enum_dispatch::enum_dispatch(ProcArea)]
+pub enum ProcShape {
+    Square,
+    Rect,
+    Circle,
+    Triangle,
+    Ring,
+}
+
+const SHAPES: usize = 1024;
+const ROUNDS: u32 = 10_000;
+
+fn shape(i: usize) -> Shape {
+    let size = (i % 7) as f64 + 1.0;
+    match i % 5 {
+        0 => Square(size).into(),
+        1 => Rect(size, size + 1.0).into(),
+        2 => Circle(size).into(),
+        3 => Triangle(size, size + 2.0).into(),
+        _ => Ring(size, size + 3.0).into(),
+    }
+}
+
+fn boxed(i: usize) -> Box<dyn Area> {
+    match shape(i) {
+        Shape::Square(v) => Box::new(v),
+        Shape::Rect(v) => Box::new(v),
+        Shape::Circle(v) => Box::new(v),
+        Shape::Triangle(v) => Box::new(v),
+        Shape::Ring(v) => Box::new(v),
+    }
+}
+
+fn proc_shape(i: usize) -> ProcShape {
+    match shape(i) {
+        Shape::Square(v) => v.into(),
+        Shape::Rect(v) => v.into(),
+        Shape::Circle(v) => v.into(),
+        Shape::Triangle(v) => v.into(),
+        Shape::Ring(v) => v.into(),
+    }
+}
+
+fn manual_area(shape: &Shape) -> f64 {
+    match shape {
+        Shape::Square(v) => v.area(),
+        Shape::Rect(v) => v.area(),
+        Shape::Circle(v) => v.area(),
+        Shape::Triangle(v) => v.area(),
+        Shape::Ring(v) => v.area(),
+    }
+}
+
+fn bench(name: &str, mut f: impl FnMut() -> f64) {
+    // warm up caches and branch predictor before measuring
+    for _ in 0..ROUNDS / 10 {
+        black_box(f());
+    }
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(f());
+    }
+    let elapsed = start.elapsed();
+    let per_call = elapsed.as_secs_f64() * 1e9 / (ROUNDS as f64 * SHAPES as f64);
+    println!("{name:<16} {elapsed:>10.3?} total, {per_call:.3} ns/call");
+}
+
+fn main() {
+    let shapes: Vec<Shape> = (0..SHAPES).map(shape).collect();
+    let boxed: Vec<Box<dyn Area>> = (0..SHAPES).map(boxed).collect();
+    let proc_shapes: Vec<ProcShape> = (0..SHAPES).map(proc_shape).collect();
+
+    bench("enum_dispatch!", || black_box(&shapes).iter().map(|shape| shape.area()).sum());
+    bench("#[enum_dispatch]", || black_box(&proc_shapes).iter().map(|shape| shape.proc_area()).sum());
+    bench("manual match", || black_box(&shapes).iter().map(manual_area).sum());
+    bench("Box<dyn Area>", || black_box(&boxed).iter().map(|shape| shape.area()).sum());
+}
@@ -0,0 +1,73 @@
+// `#[async_trait]` can't rewrite methods generated by `enum_dispatch!`, so the trait asks for the same rewrite
+// with `#[dispatch(async_trait)]` and the variants implement it with `#[async_trait]`
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use async_trait::async_trait;
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    #[dispatch(async_trait)]
+    pub trait Job: Sync {
+        async fn run(&self, n: u32) -> u32;
+
+        async fn name(&self) -> String {
+            "job".to_string()
+        }
+    }
+
+    pub enum AnyJob {
+        Double(Double),
+        Add(Add),
+    }
+);
+
+pub struct Double;
+pub struct Add(u32);
+
+#[async_trait]
+impl Job for Double {
+    async fn run(&self, n: u32) -> u32 {
+        n * 2
+    }
+}
+
+#[async_trait]
+impl Job for Add {
+    async fn run(&self, n: u32) -> u32 {
+        n + self.0
+    }
+
+    async fn name(&self) -> String {
+        format!("add {}", self.0)
+    }
+}
+
+struct Noop;
+
+impl Wake for Noop {
+    fn wake(self: Arc<Self>) {}
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(Noop));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+fn main() {
+    let jobs = [AnyJob::from(Double), AnyJob::from(Add(3))];
+    for job in &jobs {
+        println!("{}: {}", block_on(job.name()), block_on(job.run(2)));
+    }
+
+    // futures are `Send`, so the enum works with `dyn Job` the same way as the variants
+    let job: Box<dyn Job> = Box::new(AnyJob::from(Add(1)));
+    assert_eq!(block_on(job.run(2)), 3);
+}
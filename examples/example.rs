@@ -1,6 +1,10 @@
+// `platform_specific` feature only shows how `cfg` attributes are kept, the crate doesn't declare it
+#![allow(unexpected_cfgs)]
+
 use declarative_enum_dispatch::enum_dispatch;
 
 enum_dispatch!(
+    #[allow(async_fn_in_trait)]
     pub trait ShapeTrait: Clone + std::fmt::Debug + 'static {
         /// No return + default implementation
         fn print_name(&self) {
@@ -13,7 +17,7 @@ enum_dispatch!(
         /// Mutable self + arguments
         fn grow(&mut self, numerator: i32, denominator: i32,);
 
-        /// Kinda supports generics :) Bot not generic parameters, only `impl Trait`
+        /// Supports generics, both generic parameters and `impl Trait`
         fn greater(&self, other: &impl ShapeTrait) -> bool;
 
         /// Supports async methods
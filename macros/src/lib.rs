@@ -0,0 +1,389 @@
+/*!
+Attribute macro alternative of [declarative_enum_dispatch](https://docs.rs/declarative_enum_dispatch),
+it's used through `declarative_enum_dispatch::attributes` with the `proc-macro` feature of that crate.
+
+`#[enum_dispatch]` on a trait keeps the trait in a `macro_rules!` named after it,
+`#[enum_dispatch(Trait)]` on an enum calls that macro, so the impl is generated with both of them parsed
+*/
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    braced, parse_macro_input, Attribute, Error, FnArg, GenericParam, Generics, Ident, Item,
+    ItemEnum, ItemTrait, Pat, PatIdent, Path, PathArguments, ReturnType, Token, TraitItem,
+    TraitItemFn, Type, Visibility, WherePredicate,
+};
+
+/// Registers a trait for dispatch or implements registered traits for an enum.
+///
+/// Re-exported as `declarative_enum_dispatch::attributes::enum_dispatch`, see the docs of that crate
+#[proc_macro_attribute]
+pub fn enum_dispatch(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let expanded = match parse_macro_input!(item as Item) {
+        Item::Trait(item) => register_trait(attr.into(), item),
+        Item::Enum(item) => dispatch_enum(attr.into(), item),
+        item => Err(Error::new(
+            item.span(),
+            "`#[enum_dispatch]` is applied to a trait or an enum",
+        )),
+    };
+    expanded.unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Implements the trait for the enum, called by the `macro_rules!` registered for the trait.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __dispatch(input: TokenStream) -> TokenStream {
+    let Dispatch {
+        item_trait,
+        path,
+        item_enum,
+    } = parse_macro_input!(input as Dispatch);
+    implement(&item_trait, &path, &item_enum)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+// `{ trait } path::Trait { enum }`
+struct Dispatch {
+    item_trait: ItemTrait,
+    path: Path,
+    item_enum: ItemEnum,
+}
+
+impl Parse for Dispatch {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let item_trait;
+        braced!(item_trait in input);
+        let path = input.parse()?;
+        let item_enum;
+        braced!(item_enum in input);
+        Ok(Dispatch {
+            item_trait: item_trait.parse()?,
+            path,
+            item_enum: item_enum.parse()?,
+        })
+    }
+}
+
+// the enum can't read the trait from scope, so the trait is kept in a `macro_rules!` with the same name,
+// it lives in the macro namespace and is imported together with the trait
+fn register_trait(attr: TokenStream2, item: ItemTrait) -> syn::Result<TokenStream2> {
+    if !attr.is_empty() {
+        return Err(Error::new_spanned(
+            attr,
+            "`#[enum_dispatch]` on a trait takes no arguments",
+        ));
+    }
+    let name = &item.ident;
+    let callback = format_ident!("__enum_dispatch_{}", name);
+    // `macro_rules!` without `#[macro_export]` can't be used outside of the crate
+    let vis = match item.vis {
+        Visibility::Inherited => quote!(),
+        _ => quote!(pub(crate)),
+    };
+    Ok(quote! {
+        #item
+
+        #[doc(hidden)]
+        macro_rules! #callback {
+            ($($enum:tt)*) => {
+                ::declarative_enum_dispatch::attributes::__dispatch! { { #item } $($enum)* }
+            };
+        }
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        #vis use #callback as #name;
+    })
+}
+
+struct Variant<'a> {
+    cfg: Vec<&'a Attribute>,
+    ident: &'a Ident,
+    ty: &'a Type,
+}
+
+fn variants(item: &ItemEnum) -> syn::Result<Vec<Variant<'_>>> {
+    if item.variants.is_empty() {
+        return Err(Error::new_spanned(
+            &item.ident,
+            "enum without variants has nothing to dispatch to",
+        ));
+    }
+    item.variants
+        .iter()
+        .map(|variant| match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Ok(Variant {
+                cfg: variant.attrs.iter().filter(|attr| attr.path().is_ident("cfg")).collect(),
+                ident: &variant.ident,
+                ty: &fields.unnamed[0].ty,
+            }),
+            _ => Err(Error::new_spanned(
+                variant,
+                "variant should hold the value to dispatch to as a single unnamed field, e.g. `Square(Square)`",
+            )),
+        })
+        .collect()
+}
+
+// the enum is kept as is, `From` is implemented for every variant and every trait is implemented by its macro
+fn dispatch_enum(attr: TokenStream2, item: ItemEnum) -> syn::Result<TokenStream2> {
+    let traits = Punctuated::<Path, Token![,]>::parse_terminated.parse2(attr)?;
+    if traits.is_empty() {
+        return Err(Error::new_spanned(
+            &item.ident,
+            "expected traits to dispatch, e.g. `#[enum_dispatch(Shape)]`",
+        ));
+    }
+    for path in &traits {
+        let last = path.segments.last().expect("path has a segment");
+        if !matches!(last.arguments, PathArguments::None) {
+            return Err(Error::new_spanned(
+                &last.arguments,
+                "generic trait is implemented for every argument the variants implement it for, name the trait without arguments",
+            ));
+        }
+    }
+
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+    let conversions = variants(&item)?.into_iter().map(|Variant { cfg, ident, ty }| {
+        quote! {
+            #(#cfg)*
+            impl #impl_generics ::core::convert::From<#ty> for #name #ty_generics #where_clause {
+                fn from(value: #ty) -> Self {
+                    Self::#ident(value)
+                }
+            }
+        }
+    });
+    let impls = traits.iter().map(|path| quote!(#path! { #path { #item } }));
+
+    Ok(quote! {
+        #item
+        #(#conversions)*
+        #(#impls)*
+    })
+}
+
+// generic parameters of the trait and of the enum with the same name are the same parameter,
+// lifetimes go first and defaults are dropped by `split_for_impl`
+fn impl_generics(item_trait: &Generics, item_enum: &Generics) -> Generics {
+    let name = |param: &GenericParam| match param {
+        GenericParam::Lifetime(param) => param.lifetime.ident.clone(),
+        GenericParam::Type(param) => param.ident.clone(),
+        GenericParam::Const(param) => param.ident.clone(),
+    };
+    let enum_names: Vec<Ident> = item_enum.params.iter().map(name).collect();
+    let params: Vec<&GenericParam> = item_trait
+        .params
+        .iter()
+        .filter(|param| !enum_names.contains(&name(param)))
+        .chain(&item_enum.params)
+        .collect();
+    let lifetimes = params
+        .iter()
+        .filter(|param| matches!(param, GenericParam::Lifetime(_)));
+    let others = params
+        .iter()
+        .filter(|param| !matches!(param, GenericParam::Lifetime(_)));
+    Generics {
+        lt_token: Some(Default::default()),
+        params: lifetimes
+            .chain(others)
+            .map(|param| (*param).clone())
+            .collect(),
+        gt_token: Some(Default::default()),
+        where_clause: None,
+    }
+}
+
+fn implement(
+    item_trait: &ItemTrait,
+    path: &Path,
+    item_enum: &ItemEnum,
+) -> syn::Result<TokenStream2> {
+    let variants = variants(item_enum)?;
+    let (_, trait_args, _) = item_trait.generics.split_for_impl();
+    let trait_ref = quote!(#path #trait_args);
+
+    let mut predicates: Vec<WherePredicate> = Vec::new();
+    for where_clause in [
+        &item_trait.generics.where_clause,
+        &item_enum.generics.where_clause,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        predicates.extend(where_clause.predicates.iter().cloned());
+    }
+
+    // associated types are taken from the first variant, other variants have to agree with it
+    let first = variants
+        .iter()
+        .find(|variant| variant.cfg.is_empty())
+        .unwrap_or(&variants[0])
+        .ty;
+    let mut assoc = Vec::new();
+    let mut bindings = Vec::new();
+    for item in &item_trait.items {
+        match item {
+            TraitItem::Type(item) if item.generics.params.is_empty() => {
+                let ident = &item.ident;
+                assoc.push(quote!(type #ident = <#first as #trait_ref>::#ident;));
+                bindings.push(quote!(#ident = <#first as #trait_ref>::#ident));
+            }
+            TraitItem::Type(item) => {
+                return Err(Error::new_spanned(
+                    &item.generics,
+                    "generic associated types can't be dispatched",
+                ));
+            }
+            TraitItem::Const(item) if item.default.is_none() => {
+                return Err(Error::new_spanned(
+                    &item.ident,
+                    "associated constants without a default can't be dispatched",
+                ));
+            }
+            _ => {}
+        }
+    }
+    let args = item_trait.generics.params.iter().map(|param| match param {
+        GenericParam::Lifetime(param) => {
+            let lifetime = &param.lifetime;
+            quote!(#lifetime)
+        }
+        GenericParam::Type(param) => {
+            let ident = &param.ident;
+            quote!(#ident)
+        }
+        GenericParam::Const(param) => {
+            let ident = &param.ident;
+            quote!(#ident)
+        }
+    });
+    let bound = quote!(#path<#(#args,)* #(#bindings),*>);
+    // variants disabled with `#[cfg(...)]` may not exist, and predicates can't have attributes
+    for variant in variants.iter().filter(|variant| variant.cfg.is_empty()) {
+        let ty = variant.ty;
+        // binding the first variant to its own associated types never ends
+        let bound = if std::ptr::eq(ty, first) {
+            &trait_ref
+        } else {
+            &bound
+        };
+        predicates.push(syn::parse_quote!(#ty: #bound));
+    }
+
+    let methods = item_trait
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            TraitItem::Fn(method) => method_impl(method, &trait_ref, &variants).transpose(),
+            _ => None,
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let generics = impl_generics(&item_trait.generics, &item_enum.generics);
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, _) = item_enum.generics.split_for_impl();
+    let name = &item_enum.ident;
+    Ok(quote! {
+        impl #impl_generics #trait_ref for #name #ty_generics where #(#predicates,)* {
+            #(#assoc)*
+            #(#methods)*
+        }
+    })
+}
+
+// every variant value is passed to the method of its type, arguments are renamed, so patterns of the trait are allowed
+fn method_impl(
+    method: &TraitItemFn,
+    trait_ref: &TokenStream2,
+    variants: &[Variant<'_>],
+) -> syn::Result<Option<TokenStream2>> {
+    let mut sig = method.sig.clone();
+    let name = &sig.ident;
+    match sig.receiver() {
+        // associated function keeps its default implementation
+        None if method.default.is_some() => return Ok(None),
+        None => {
+            return Err(Error::new_spanned(
+                &sig,
+                format!("associated function `{name}` has no value to dispatch on, it needs a default implementation"),
+            ))
+        }
+        Some(receiver) if receiver.colon_token.is_some() => {
+            return Err(Error::new_spanned(receiver, "only `self`, `&self` and `&mut self` receivers are supported"))
+        }
+        Some(_) => {}
+    }
+
+    let mut args = Vec::new();
+    for (index, input) in sig.inputs.iter_mut().enumerate() {
+        match input {
+            // `mut self` is moved into the variant, so `mut` would be unused
+            FnArg::Receiver(receiver) if receiver.reference.is_none() => receiver.mutability = None,
+            FnArg::Receiver(_) => {}
+            FnArg::Typed(arg) => {
+                let ident = Ident::new(&format!("arg{index}"), Span::mixed_site());
+                arg.attrs.clear();
+                *arg.pat = Pat::Ident(PatIdent {
+                    attrs: Vec::new(),
+                    by_ref: None,
+                    mutability: None,
+                    ident: ident.clone(),
+                    subpat: None,
+                });
+                args.push(ident);
+            }
+        }
+    }
+
+    let turbofish: Vec<&Ident> = sig
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(&param.ident),
+            GenericParam::Const(param) => Some(&param.ident),
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect();
+    let turbofish = (!turbofish.is_empty()).then(|| quote!(::<#(#turbofish),*>));
+    let returns_self = matches!(&sig.output, ReturnType::Type(_, ty) if matches!(&**ty, Type::Path(ty) if ty.qself.is_none() && ty.path.is_ident("Self")));
+    let value = Ident::new("value", Span::mixed_site());
+    let arms = variants.iter().map(|Variant { cfg, ident, ty }| {
+        let mut call = quote!(<#ty as #trait_ref>::#name #turbofish(#value, #(#args),*));
+        if sig.asyncness.is_some() {
+            call = quote!(#call.await);
+        }
+        if sig.unsafety.is_some() {
+            call = quote!(unsafe { #call });
+        }
+        if returns_self {
+            call = quote!(Self::#ident(#call));
+        }
+        quote!(#(#cfg)* Self::#ident(#value) => #call,)
+    });
+    let cfg = method
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"));
+    let allow = sig.unsafety.map(|_| quote!(#[allow(unused_unsafe)]));
+
+    Ok(Some(quote! {
+        #(#cfg)*
+        #allow
+        #[inline]
+        #sig {
+            match self {
+                #(#arms)*
+            }
+        }
+    }))
+}
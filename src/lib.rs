@@ -3,11 +3,12 @@
 
 
 Generate boilerplate code for dynamic dispatch of a trait using an enum.
-Also generates From for every enum variant
+Also generates From for every enum variant and, on request, TryFrom to get the variant back
 
 This is a fully declarative version of [enum_dispatch](https://docs.rs/enum_dispatch) macro
 
 For benchmarks look at [enum_dispatch benchmarks](https://docs.rs/enum_dispatch/latest/enum_dispatch/#performance) crate
+and `cargo bench` compares generated dispatch with the `enum_dispatch` crate, a hand-written `match` and `Box<dyn Trait>`
 
 Usage example:
 ```
@@ -26,7 +27,7 @@ enum_dispatch!(
         /// Mutable self + arguments
         fn grow(&mut self, numerator: i32, denominator: i32,);
 
-        /// Kinda supports generics :) Bot not generic parameters, only `impl Trait`
+        /// Supports generics, both generic parameters and `impl Trait`
         fn greater(&self, other: &impl ShapeTrait) -> bool;
 
         /// Supports async methods
@@ -114,7 +115,7 @@ pub trait ShapeTrait: Clone + std::fmt::Debug + 'static {
     fn area(&self) -> i32;
     /// Mutable self + arguments
     fn grow(&mut self, numerator: i32, denominator: i32);
-    /// Kinda supports generics :) Bot not generic parameters, only `impl Trait`
+    /// Supports generics, both generic parameters and `impl Trait`
     fn greater(&self, other: &impl ShapeTrait) -> bool;
     /// Supports async methods
     async fn send(&self);
@@ -130,83 +131,91 @@ pub enum Shape {
     Cube(Cube),
 }
 impl ShapeTrait for Shape {
-    /// No return + default implementation
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    #[inline]
     fn print_name(&self) {
         match self {
-            Shape::Rect(v) => v.print_name(),
-            Shape::Circle(v) => v.print_name(),
+            Shape::Rect(v) => ShapeTrait::print_name(v),
+            Shape::Circle(v) => ShapeTrait::print_name(v),
             #[cfg(feature = "platform_specific")]
-            Shape::Cube(v) => v.print_name(),
+            Shape::Cube(v) => ShapeTrait::print_name(v),
         }
     }
-    /// Basic call without arguments
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    #[inline]
     fn name(&self) -> String {
         match self {
-            Shape::Rect(v) => v.name(),
-            Shape::Circle(v) => v.name(),
+            Shape::Rect(v) => ShapeTrait::name(v),
+            Shape::Circle(v) => ShapeTrait::name(v),
             #[cfg(feature = "platform_specific")]
-            Shape::Cube(v) => v.name(),
+            Shape::Cube(v) => ShapeTrait::name(v),
         }
     }
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    #[inline]
     fn area(&self) -> i32 {
         match self {
-            Shape::Rect(v) => v.area(),
-            Shape::Circle(v) => v.area(),
+            Shape::Rect(v) => ShapeTrait::area(v),
+            Shape::Circle(v) => ShapeTrait::area(v),
             #[cfg(feature = "platform_specific")]
-            Shape::Cube(v) => v.area(),
+            Shape::Cube(v) => ShapeTrait::area(v),
         }
     }
-    /// Mutable self + arguments
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    #[inline]
     fn grow(&mut self, numerator: i32, denominator: i32) {
         match self {
-            Shape::Rect(v) => v.grow(numerator, denominator),
-            Shape::Circle(v) => v.grow(numerator, denominator),
+            Shape::Rect(v) => ShapeTrait::grow(v, numerator, denominator),
+            Shape::Circle(v) => ShapeTrait::grow(v, numerator, denominator),
             #[cfg(feature = "platform_specific")]
-            Shape::Cube(v) => v.grow(numerator, denominator),
+            Shape::Cube(v) => ShapeTrait::grow(v, numerator, denominator),
         }
     }
-    /// Kinda supports generics :) Bot not generic parameters, only `impl Trait`
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    #[inline]
     fn greater(&self, other: &impl ShapeTrait) -> bool {
         match self {
-            Shape::Rect(v) => v.greater(other),
-            Shape::Circle(v) => v.greater(other),
+            Shape::Rect(v) => ShapeTrait::greater(v, other),
+            Shape::Circle(v) => ShapeTrait::greater(v, other),
             #[cfg(feature = "platform_specific")]
-            Shape::Cube(v) => v.greater(other),
+            Shape::Cube(v) => ShapeTrait::greater(v, other),
         }
     }
-    /// Supports async methods
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    #[inline]
     async fn send(&self) {
         match self {
-            Shape::Rect(v) => v.send().await,
-            Shape::Circle(v) => v.send().await,
+            Shape::Rect(v) => ShapeTrait::send(v).await,
+            Shape::Circle(v) => ShapeTrait::send(v).await,
             #[cfg(feature = "platform_specific")]
-            Shape::Cube(v) => v.send().await,
+            Shape::Cube(v) => ShapeTrait::send(v).await,
         }
     }
-    /// Works with attributes
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    #[inline]
     #[cfg(feature = "platform_specific")]
     fn platform_specific(self) {
         match self {
-            Shape::Rect(v) => v.platform_specific(),
-            Shape::Circle(v) => v.platform_specific(),
+            Shape::Rect(v) => ShapeTrait::platform_specific(v),
+            Shape::Circle(v) => ShapeTrait::platform_specific(v),
             #[cfg(feature = "platform_specific")]
-            Shape::Cube(v) => v.platform_specific(),
+            Shape::Cube(v) => ShapeTrait::platform_specific(v),
         }
     }
 }
 impl From<Rect> for Shape {
-    fn from(value: Rect) -> Shape {
+    fn from(value: Rect) -> Self {
         Shape::Rect(value)
     }
 }
 impl From<Circle> for Shape {
-    fn from(value: Circle) -> Shape {
+    fn from(value: Circle) -> Self {
         Shape::Circle(value)
     }
 }
 #[cfg(feature = "platform_specific")]
 impl From<Cube> for Shape {
-    fn from(value: Cube) -> Shape {
+    fn from(value: Cube) -> Self {
         Shape::Cube(value)
     }
 }
@@ -257,109 +266,5530 @@ impl From<Cube> for Shape {
 
 ```
 
-*/
+## Smart pointer receivers
+Besides `self`, `&self` and `&mut self` methods can take `self: Box<Self>`, `self: Rc<Self>`,
+`self: Arc<Self>` or `self: &Arc<Self>`.
+The boxed enum is unpacked and the variant value is boxed again before the call.
+Shared pointers can't be moved out of, so for `Rc`/`Arc` receivers the enum has to be `Clone`:
+the inner value is taken with `unwrap_or_clone` (or cloned for `&Arc<Self>`) and wrapped into a new pointer
+```
+use declarative_enum_dispatch::enum_dispatch;
+use std::sync::Arc;
 
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __build_method {
-    ($(#[$attr:meta])* $($method_def:ident)+; {$($self_ref:tt)*}; $self_:ident; { $($arg:ident: $arg_ty:ty),* }; $( -> $return_type:ty)?; [$($(#[$var_attr:meta])* $variant:ident),+]; $enum_name:ident) => {
-        $(#[$attr])* $($method_def)+($($self_ref)* $self_, $($arg: $arg_ty),*) $( -> $return_type)? {
-            $crate::__build_method!(@make_match $self_; $($method_def)+; $enum_name; [$($(#[$var_attr])* $variant),+]; ($($arg),*))
-        }
-    };
+enum_dispatch!(
+    pub trait State {
+        fn next(self: Box<Self>, input: char) -> Option<char>;
+        fn spawn(self: Arc<Self>) -> usize;
+        fn peek(self: &Arc<Self>) -> usize;
+    }
 
-    (@make_match $self_:ident; fn $method:ident; $enum_name:ident; [$($(#[$var_attr:meta])* $variant:ident),+]; $args:tt) => {
-        match $self_ {
-            $(
-                $(#[$var_attr])*
-                $enum_name::$variant(v) => v.$method $args
-            ),+
-        }
-    };
-    (@make_match $self_:ident; async fn $method:ident; $enum_name:ident; [$($(#[$var_attr:meta])* $variant:ident),+]; $args:tt) => {
-        match $self_ {
-            $(
-                $(#[$var_attr])*
-                $enum_name::$variant(v) => v.$method $args .await
-            ),+
-        }
-    };
+    #[derive(Clone)]
+    pub enum Machine {
+        Start(Start),
+    }
+);
+
+#[derive(Clone)]
+pub struct Start;
+
+impl State for Start {
+    fn next(self: Box<Self>, input: char) -> Option<char> {
+        input.is_alphabetic().then_some(input)
+    }
+    fn spawn(self: Arc<Self>) -> usize {
+        Arc::strong_count(&self)
+    }
+    fn peek(self: &Arc<Self>) -> usize {
+        Arc::strong_count(self)
+    }
 }
 
-#[macro_export]
-#[doc(hidden)]
-// muncher for list of methods declared on trait
-// there is 3 variants for `self`, `&self`, `&mut self` because declarative macro can't handle self pattern
-macro_rules! __munch_methods {
-    ({ }; [$($(#[$var_attr:meta])* $variant:ident),+]; $enum_name:ident) => {};
+let machine = Box::new(Machine::from(Start));
+assert_eq!(machine.next('a'), Some('a'));
 
-    // variants without block
-    ({ $(#[$attr:meta])* $($method_def:ident)+($self_:ident $(, $($arg:ident: $arg_ty:ty),*)? $(,)?) $( -> $return_type:ty)?; $($rest:tt)* }; $variants:tt; $enum_name:ident) => {
-        $crate::__build_method!($(#[$attr])* $($method_def)+; { }; $self_; { $($($arg: $arg_ty),*)? }; $( -> $return_type)?; $variants; $enum_name);
-        $crate::__munch_methods!({ $($rest)* }; $variants; $enum_name);
-    };
-    ({ $(#[$attr:meta])* $($method_def:ident)+(&$self_:ident $(, $($arg:ident: $arg_ty:ty),*)? $(,)?) $( -> $return_type:ty)?;  $($rest:tt)* }; $variants:tt; $enum_name:ident) => {
-        $crate::__build_method!($(#[$attr])* $($method_def)+; { & }; $self_; { $($($arg: $arg_ty),*)? }; $( -> $return_type)?; $variants; $enum_name);
-        $crate::__munch_methods!({ $($rest)* }; $variants; $enum_name);
-    };
-    ({ $(#[$attr:meta])* $($method_def:ident)+(&mut $self_:ident $(, $($arg:ident: $arg_ty:ty),*)? $(,)?) $( -> $return_type:ty)?; $($rest:tt)* }; $variants:tt; $enum_name:ident) => {
-        $crate::__build_method!($(#[$attr])* $($method_def)+; { &mut }; $self_; { $($($arg: $arg_ty),*)? }; $( -> $return_type)?; $variants; $enum_name);
-        $crate::__munch_methods!({ $($rest)* }; $variants; $enum_name);
-    };
+let machine = Arc::new(Machine::from(Start));
+assert_eq!(machine.peek(), 1);
+assert_eq!(machine.spawn(), 1);
+```
 
-    // variants with block
-    ({ $(#[$attr:meta])* $($method_def:ident)+($self_:ident $(, $($arg:ident: $arg_ty:ty),*)? $(,)?) $( -> $return_type:ty)? $body:block $($rest:tt)* }; $variants:tt; $enum_name:ident) => {
-        $crate::__build_method!($(#[$attr])* $($method_def)+; { }; $self_; { $($($arg: $arg_ty),*)? }; $( -> $return_type)?; $variants; $enum_name);
-        $crate::__munch_methods!({ $($rest)* }; $variants; $enum_name);
-    };
-    ({ $(#[$attr:meta])* $($method_def:ident)+(&$self_:ident $(, $($arg:ident: $arg_ty:ty),*)? $(,)?) $( -> $return_type:ty)? $body:block $($rest:tt)* }; $variants:tt; $enum_name:ident) => {
-        $crate::__build_method!($(#[$attr])* $($method_def)+; { & }; $self_; { $($($arg: $arg_ty),*)? }; $( -> $return_type)?; $variants; $enum_name);
-        $crate::__munch_methods!({ $($rest)* }; $variants; $enum_name);
-    };
-    ({ $(#[$attr:meta])* $($method_def:ident)+(&mut $self_:ident $(, $($arg:ident: $arg_ty:ty),*)? $(,)?) $( -> $return_type:ty)? $body:block $($rest:tt)* }; $variants:tt; $enum_name:ident) => {
-        $crate::__build_method!($(#[$attr])* $($method_def)+; { &mut }; $self_; { $($($arg: $arg_ty),*)? }; $( -> $return_type)?; $variants; $enum_name);
-        $crate::__munch_methods!({ $($rest)* }; $variants; $enum_name);
-    };
+Other pointer types are rejected, the generated method wouldn't know how to rebuild them
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+use std::pin::Pin;
+
+enum_dispatch!(
+    pub trait State {
+        // error: receiver `Pin<Self>` of method `fn next` is not supported, expected `Box<Self>`, `Rc<Self>` or `Arc<Self>`
+        fn next(self: Pin<Self>) -> Option<char>;
+    }
+
+    pub enum Machine {
+        Start(Start),
+    }
+);
+
+pub struct Start;
+```
+
+## Generic methods
+Methods can declare lifetime, type and const generic parameters, the bounds are copied to the generated method.
+Type and const parameters are forwarded with turbofish, receivers can have explicit lifetime (`&'a self`, `&'a mut self`)
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Storage {
+        fn insert<K: Into<String>>(&mut self, key: K);
+        fn len_of<A: AsRef<[u8]>, B: Into<Vec<Vec<u8>>>>(&self, a: A, b: B) -> usize;
+        fn fill<const N: usize>(&self) -> [u8; N];
+        fn repeat<'a, T: Clone, const N: usize>(&self, value: &'a T) -> [T; N];
+        fn view<'a>(&'a self) -> Cursor<'a>;
+        fn view_mut<'a>(&'a mut self) -> &'a mut Vec<String>;
+    }
+
+    pub enum AnyStorage {
+        Memory(Memory),
+    }
+);
+
+#[derive(Default)]
+pub struct Memory(Vec<String>);
+
+impl Storage for Memory {
+    fn insert<K: Into<String>>(&mut self, key: K) {
+        self.0.push(key.into());
+    }
+    fn len_of<A: AsRef<[u8]>, B: Into<Vec<Vec<u8>>>>(&self, a: A, b: B) -> usize {
+        a.as_ref().len() + b.into().len()
+    }
+    fn fill<const N: usize>(&self) -> [u8; N] {
+        [self.0.len() as u8; N]
+    }
+    fn repeat<'a, T: Clone, const N: usize>(&self, value: &'a T) -> [T; N] {
+        std::array::from_fn(|_| value.clone())
+    }
+    fn view<'a>(&'a self) -> Cursor<'a> {
+        Cursor(&self.0)
+    }
+    fn view_mut<'a>(&'a mut self) -> &'a mut Vec<String> {
+        &mut self.0
+    }
+}
+
+pub struct Cursor<'a>(&'a [String]);
+
+let mut storage = AnyStorage::from(Memory::default());
+storage.insert("key");
+assert_eq!(storage.len_of("abc", vec![vec![1]]), 4);
+assert_eq!(storage.fill::<2>(), [1, 1]);
+assert_eq!(storage.repeat::<_, 3>(&'a'), ['a'; 3]);
+storage.view_mut().push("other".to_string());
+assert_eq!(storage.view().0.len(), 2);
+```
+
+Closures bound by `Fn`, `FnMut` and `FnOnce` are moved into the call of the variant value, bounds stay the same as in the trait
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Numbers {
+        fn for_each<F: FnMut(i32)>(&self, f: F);
+        fn fold<T, F>(&self, init: T, f: F) -> T where F: Fn(T, i32) -> T;
+        fn consume<F: FnOnce(Vec<i32>) -> usize>(self, f: F) -> usize where Self: Sized;
+    }
+
+    pub enum AnyNumbers {
+        List(List),
+    }
+);
+
+pub struct List(Vec<i32>);
 
-    ({ $(#[$attr:meta])* $($method_def:ident)+($($args:tt)*) $($rest:tt)* }; [$($(#[$var_attr:meta])* $variant:ident),+]; $enum_name:ident ) => {
-        compile_error!(concat!("method `", stringify!($($method_def)+), "` should receive self"));
+impl Numbers for List {
+    fn for_each<F: FnMut(i32)>(&self, mut f: F) {
+        self.0.iter().for_each(|x| f(*x));
+    }
+    fn fold<T, F>(&self, init: T, f: F) -> T where F: Fn(T, i32) -> T {
+        self.0.iter().fold(init, |acc, x| f(acc, *x))
+    }
+    fn consume<F: FnOnce(Vec<i32>) -> usize>(self, f: F) -> usize {
+        f(self.0)
     }
 }
 
-#[macro_export]
-macro_rules! enum_dispatch {
-    (
-        $(#[$trait_attr:meta])*
-        $trait_vis:vis trait $train_name:ident $(: $lf:lifetime)? $(: $super_trait1:ident $(::$super_trait2:ident)* $(+ $super_trait3:ident $(::$super_trait4:ident)*)*)? $(+ $lf2:lifetime)? {
-            $($any:tt)*
-        }
+let numbers = AnyNumbers::from(List(vec![1, 2, 3]));
+let mut sum = 0;
+numbers.for_each(|x| sum += x);
+assert_eq!(sum, 6);
+assert_eq!(numbers.fold(String::new(), |acc, x| acc + &x.to_string()), "123");
+let extra = vec![0];
+assert_eq!(numbers.consume(move |list| list.len() + extra.len()), 4);
+```
 
-        $(#[$enum_attr:meta])*
-        $enum_vis:vis enum $enum_name:ident {
-            $($(#[$var_attr:meta])* $variant:ident($variant_type:ty)),+$(,)?
-        }
-    ) => {
-        $(#[$trait_attr])*
-        $trait_vis trait $train_name $(: $lf)? $(: $super_trait1 $(::$super_trait2)* $(+ $super_trait3 $(::$super_trait4)*)*)? $(+ $lf2)? {
-            $($any)*
-        }
+Return type can name associated types of the method generics, both `T::Err` and `<T as Trait>::Err`
+```
+use std::str::FromStr;
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Parser {
+        fn parse<T: FromStr>(&self, input: &str) -> Result<T, T::Err>;
+        fn parse_all<T>(&self, input: &str) -> Option<Result<T, <T as FromStr>::Err>> where T: FromStr;
+    }
+
+    pub enum AnyParser {
+        Trimmed(Trimmed),
+    }
+);
+
+pub struct Trimmed;
+
+impl Parser for Trimmed {
+    fn parse<T: FromStr>(&self, input: &str) -> Result<T, T::Err> {
+        input.trim().parse()
+    }
+    fn parse_all<T>(&self, input: &str) -> Option<Result<T, <T as FromStr>::Err>> where T: FromStr {
+        Some(input.trim().parse())
+    }
+}
+
+let parser = AnyParser::from(Trimmed);
+assert_eq!(parser.parse::<u32>(" 5 "), Ok(5));
+assert!(parser.parse::<u32>("five").is_err());
+assert_eq!(parser.parse_all::<i8>(" -1"), Some(Ok(-1)));
+```
+
+Generics used only in the return type are forwarded with turbofish too, so the variant call doesn't rely on inference
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Number {
+        fn cast<T: From<i32>>(&self) -> T;
+        fn cast_all<T, const N: usize>(&self) -> [T; N] where T: From<i32> + Copy;
+    }
+
+    pub enum AnyNumber {
+        Small(Small),
+        Big(Big),
+    }
+);
+
+pub struct Small(i8);
+pub struct Big(i32);
+
+impl Number for Small {
+    fn cast<T: From<i32>>(&self) -> T {
+        T::from(self.0 as i32)
+    }
+    fn cast_all<T, const N: usize>(&self) -> [T; N] where T: From<i32> + Copy {
+        [self.cast(); N]
+    }
+}
+impl Number for Big {
+    fn cast<T: From<i32>>(&self) -> T {
+        T::from(self.0)
+    }
+    fn cast_all<T, const N: usize>(&self) -> [T; N] where T: From<i32> + Copy {
+        [self.cast(); N]
+    }
+}
+
+let number = AnyNumber::from(Big(7));
+let value: i64 = number.cast();
+assert_eq!(value, 7);
+assert_eq!(number.cast::<f64>(), 7.0);
+assert_eq!(AnyNumber::from(Small(-1)).cast_all::<i64, 2>(), [-1, -1]);
+```
+
+Defaults of type parameters are kept on the trait declaration and left out of the generated method,
+where they aren't allowed. Rust denies them on methods with `invalid_type_param_default` lint, so it has to be allowed
+```
+use declarative_enum_dispatch::enum_dispatch;
 
-        $(#[$enum_attr])*
-        $enum_vis enum $enum_name {
-            $($(#[$var_attr])* $variant($variant_type)),+
+enum_dispatch!(
+    pub trait Numbers {
+        #[allow(invalid_type_param_default)]
+        fn collect<C: Default + Extend<i32> = Vec<i32>>(&self) -> C;
+    }
+
+    pub enum AnyNumbers {
+        List(List),
+    }
+);
+
+pub struct List(Vec<i32>);
+
+impl Numbers for List {
+    fn collect<C: Default + Extend<i32>>(&self) -> C {
+        let mut collection = C::default();
+        collection.extend(self.0.iter().copied());
+        collection
+    }
+}
+
+let numbers = AnyNumbers::from(List(vec![1, 2]));
+assert_eq!(numbers.collect::<Vec<i32>>(), [1, 2]);
+assert_eq!(numbers.collect::<std::collections::BTreeSet<i32>>().len(), 2);
+```
+
+## Where clauses
+Methods can have `where` clauses, they are copied to the generated method
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Buffer {
+        fn merge<T>(&mut self, other: T) where T: IntoIterator<Item = u8>;
+        fn total(&self) -> usize where Self: Sized {
+            0
         }
+    }
+
+    pub enum AnyBuffer {
+        Bytes(Bytes),
+    }
+);
+
+#[derive(Default)]
+pub struct Bytes(Vec<u8>);
+
+impl Buffer for Bytes {
+    fn merge<T>(&mut self, other: T) where T: IntoIterator<Item = u8> {
+        self.0.extend(other);
+    }
+    fn total(&self) -> usize {
+        self.0.len()
+    }
+}
+
+let mut buffer = AnyBuffer::from(Bytes::default());
+buffer.merge([1, 2, 3]);
+assert_eq!(buffer.total(), 3);
+```
+
+`where Self: Sized` keeps the trait dyn compatible, `Self` of the generated method is the enum, so such methods are dispatched too
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+        fn clone_box(&self) -> Box<dyn Area> where Self: Sized;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+#[derive(Clone)]
+pub struct Square(f32);
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+    fn clone_box(&self) -> Box<dyn Area> {
+        Box::new(self.clone())
+    }
+}
+
+let shapes: Vec<Box<dyn Area>> = vec![AnyShape::from(Square(2.0)).clone_box(), Box::new(Square(1.0))];
+assert_eq!(shapes.iter().map(|shape| shape.area()).sum::<f32>(), 5.0);
+```
+
+## Compound return types
+Return types other than a simple path are collected token by token until `;`, `where` or the body,
+so tuples, arrays, trait objects and nested generics closed with `>>` are returned as is
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Shape {
+        fn bounds(&self) -> ((i32, i32), (i32, i32));
+        fn nested(&self) -> Option<Vec<Vec<i32>>>;
+    }
+
+    pub enum AnyShape {
+        Rect(Rect),
+    }
+);
+
+pub struct Rect;
+
+impl Shape for Rect {
+    fn bounds(&self) -> ((i32, i32), (i32, i32)) {
+        ((0, 0), (1, 1))
+    }
+    fn nested(&self) -> Option<Vec<Vec<i32>>> {
+        Some(vec![vec![1, 2]])
+    }
+}
+
+let shape = AnyShape::from(Rect);
+assert_eq!(shape.bounds(), ((0, 0), (1, 1)));
+assert_eq!(shape.nested(), Some(vec![vec![1, 2]]));
+```
+
+## Returning `impl Trait`
+Every variant returns its own type for `impl Trait`, so a plain `match` can't be used to dispatch such method.
+Mark it with `#[dispatch(box)]` and every variant's value is boxed into `Box<dyn Trait>`,
+trait declaration stays the same and the marker is removed from it
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Tree {
+        #[dispatch(box)]
+        fn children(&self) -> impl Iterator<Item = u32>;
+    }
+
+    pub enum Node {
+        Leaf(Leaf),
+        Branch(Branch),
+    }
+);
+
+pub struct Leaf;
+pub struct Branch(Vec<u32>);
+
+impl Tree for Leaf {
+    fn children(&self) -> impl Iterator<Item = u32> {
+        std::iter::empty()
+    }
+}
+
+impl Tree for Branch {
+    fn children(&self) -> impl Iterator<Item = u32> {
+        self.0.iter().copied()
+    }
+}
+
+assert_eq!(Node::from(Leaf).children().count(), 0);
+assert_eq!(Node::from(Branch(vec![1, 2])).children().sum::<u32>(), 3);
+```
+
+Only the generated method of the enum is boxed, default implementation in the trait is kept as is,
+so variant types that don't override it still return their own unboxed type
+```
+use declarative_enum_dispatch::enum_dispatch;
 
-        impl $train_name for $enum_name {
-            $crate::__munch_methods!({ $($any)* }; [$($(#[$var_attr])* $variant),+]; $enum_name);
+enum_dispatch!(
+    pub trait Tree {
+        #[dispatch(box)]
+        fn children(&self) -> impl Iterator<Item = u32>;
+        #[dispatch(box)]
+        fn even_children(&self) -> impl Iterator<Item = u32> + '_ {
+            self.children().filter(|child| child % 2 == 0)
         }
+    }
 
-        $(
-            $(#[$var_attr])*
-            impl From<$variant_type> for $enum_name {
-                 fn from(value: $variant_type) -> $enum_name {
-                     $enum_name::$variant(value)
-                 }
-            }
-        )+
-    };
+    pub enum Node {
+        Branch(Branch),
+    }
+);
+
+pub struct Branch(Vec<u32>);
+
+impl Tree for Branch {
+    fn children(&self) -> impl Iterator<Item = u32> {
+        self.0.clone().into_iter()
+    }
+}
+
+assert_eq!(Branch(vec![1, 2, 4]).even_children().sum::<u32>(), 6);
+assert_eq!(Node::from(Branch(vec![1, 2, 4])).even_children().sum::<u32>(), 6);
+```
+
+Bounds of `impl Trait` are kept on the boxed trait object, so values returned as `impl Iterator<Item = Self::Event> + Send + '_`
+are boxed into `Box<dyn Iterator<Item = Self::Event> + Send + '_>` with associated type chosen for the enum
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Stream {
+        type Event;
+
+        #[dispatch(box)]
+        fn events(&self) -> impl Iterator<Item = Self::Event> + '_;
+        #[dispatch(box)]
+        fn tagged<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = (&'a str, Self::Event)> + Send + 'a;
+    }
+
+    pub enum AnyStream {
+        Log(Log),
+        Idle,
+    }
+    with type Event = u32;
+);
+
+pub struct Log(Vec<u32>);
+pub struct Idle;
+
+impl Stream for Log {
+    type Event = u32;
+    fn events(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.iter().copied()
+    }
+    fn tagged<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = (&'a str, u32)> + Send + 'a {
+        self.0.iter().map(move |event| (tag, *event))
+    }
+}
+
+impl Stream for Idle {
+    type Event = u32;
+    fn events(&self) -> impl Iterator<Item = u32> + '_ {
+        std::iter::empty()
+    }
+    fn tagged<'a>(&'a self, _tag: &'a str) -> impl Iterator<Item = (&'a str, u32)> + Send + 'a {
+        std::iter::empty()
+    }
+}
+
+let log = AnyStream::from(Log(vec![1, 2]));
+assert_eq!(log.events().sum::<u32>(), 3);
+assert_eq!(AnyStream::Idle.events().count(), 0);
+
+let tag = String::from("log");
+let tagged = std::thread::scope(|scope| scope.spawn(|| log.tagged(&tag).collect::<Vec<_>>()).join().unwrap());
+assert_eq!(tagged, [("log", 1), ("log", 2)]);
+```
+
+## Associated types
+Trait can declare associated types. All variants have to agree on them,
+so the enum binds every associated type once with `with type Name = Type;` after the enum
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Measure {
+        type Output: std::fmt::Debug;
+
+        fn measure(&self) -> Self::Output;
+    }
+
+    pub enum Shape {
+        Square(Square),
+        Line(Line),
+    }
+    with type Output = u32;
+);
+
+pub struct Square(u32);
+pub struct Line(u32);
+
+impl Measure for Square {
+    type Output = u32;
+    fn measure(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+impl Measure for Line {
+    type Output = u32;
+    fn measure(&self) -> u32 {
+        self.0
+    }
+}
+
+assert_eq!(Shape::from(Square(3)).measure(), 9);
+assert_eq!(Shape::from(Line(3)).measure(), 3);
+```
+
+Arguments can refer to the associated types too, including bounds of `impl Trait` and generic arguments
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Sink {
+        type Item;
+
+        fn consume(&self, iter: impl Iterator<Item = Self::Item>) -> usize;
+        fn extend<I: IntoIterator<Item = Self::Item>>(&mut self, items: I);
+        fn all(&self, f: impl Fn(&Self::Item) -> bool) -> bool;
+    }
+
+    pub enum AnySink {
+        Counter(Counter),
+        Collector(Collector),
+    }
+    with type Item = i32;
+);
+
+pub struct Counter(usize);
+pub struct Collector(Vec<i32>);
+
+impl Sink for Counter {
+    type Item = i32;
+    fn consume(&self, iter: impl Iterator<Item = i32>) -> usize {
+        self.0 + iter.count()
+    }
+    fn extend<I: IntoIterator<Item = i32>>(&mut self, items: I) {
+        self.0 += items.into_iter().count();
+    }
+    fn all(&self, _f: impl Fn(&i32) -> bool) -> bool {
+        true
+    }
+}
+
+impl Sink for Collector {
+    type Item = i32;
+    fn consume(&self, iter: impl Iterator<Item = i32>) -> usize {
+        self.0.len() + iter.count()
+    }
+    fn extend<I: IntoIterator<Item = i32>>(&mut self, items: I) {
+        self.0.extend(items);
+    }
+    fn all(&self, f: impl Fn(&i32) -> bool) -> bool {
+        self.0.iter().all(f)
+    }
+}
+
+let mut counter = AnySink::from(Counter(0));
+counter.extend([1, 2]);
+assert_eq!(counter.consume(0..3), 5);
+
+let mut collector = AnySink::from(Collector(vec![]));
+collector.extend(vec![1, 2]);
+assert!(collector.all(|x| *x > 0));
+assert_eq!(collector.consume([3].into_iter()), 3);
+```
+
+## Variant predicates
+Declarative macros can't change the case of identifiers, so `is_*` predicates are generated only
+for the variants that name them with `as`. Predicates respect `#[cfg(...)]` on the variant
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Job {
+        fn run(&self);
+    }
+
+    pub enum AnyJob {
+        Idle(Idle) as is_idle,
+        Busy(Busy) as is_busy,
+        Other(Other),
+    }
+);
+
+pub struct Idle;
+pub struct Busy;
+pub struct Other;
+
+impl Job for Idle {
+    fn run(&self) {}
+}
+impl Job for Busy {
+    fn run(&self) {}
+}
+impl Job for Other {
+    fn run(&self) {}
+}
+
+assert!(AnyJob::from(Idle).is_idle());
+assert!(!AnyJob::from(Other).is_busy());
+```
+
+## Variants with multiple fields
+Variant can hold several fields, the one implementing the trait is marked with `#[dispatch]`.
+Conversions (`From`, `TryFrom`, `as_variant`) are generated only for variants with a single field
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Draw {
+        fn draw(&self) -> String;
+    }
+
+    pub enum Figure {
+        Dot(Dot),
+        Line(#[dispatch] Line, Color) as is_line,
+    }
+);
+
+pub struct Dot;
+pub struct Line(i32, i32);
+pub struct Color;
+
+impl Draw for Dot {
+    fn draw(&self) -> String {
+        ".".to_string()
+    }
+}
+impl Draw for Line {
+    fn draw(&self) -> String {
+        "-".repeat((self.1 - self.0) as usize)
+    }
+}
+
+let figure = Figure::Line(Line(1, 4), Color);
+assert!(figure.is_line());
+assert_eq!(figure.draw(), "---");
+assert_eq!(Figure::from(Dot).draw(), ".");
+```
+
+Field implementing the trait has to be marked
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Draw {
+        fn draw(&self) -> String;
+    }
+
+    pub enum Figure {
+        Line(Line, Color),
+    }
+);
+# pub struct Line;
+# pub struct Color;
+```
+
+## Unit variants
+Unit variant dispatches to the unit struct with the same name, value is constructed for every call.
+Conversions are not generated for unit variants
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Status {
+        fn code(&self) -> u32;
+    }
+
+    pub enum Connection {
+        Idle as is_idle,
+        Active(Active),
+    }
+);
+
+pub struct Idle;
+pub struct Active(u32);
+
+impl Status for Idle {
+    fn code(&self) -> u32 {
+        0
+    }
+}
+impl Status for Active {
+    fn code(&self) -> u32 {
+        self.0
+    }
+}
+
+assert!(Connection::Idle.is_idle());
+assert_eq!(Connection::Idle.code(), 0);
+assert_eq!(Connection::from(Active(200)).code(), 200);
+```
+
+## Disabling conversions
+`From` and `TryFrom` impls of variants wrapping the same type conflict with each other.
+Mark variant with `#[dispatch(no_from)]` to skip its conversions, dispatch is generated as usual
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Amount {
+        fn cents(&self) -> u32;
+    }
+
+    pub enum Price {
+        /// Price in cents
+        Cents(u32),
+        #[dispatch(no_from)]
+        Discounted(u32),
+    }
+);
+
+impl Amount for u32 {
+    fn cents(&self) -> u32 {
+        *self
+    }
+}
+
+assert_eq!(Price::from(150).cents(), 150);
+assert_eq!(Price::Discounted(100).cents(), 100);
+```
+
+Generated items can be picked with `#[dispatch(generate(...))]` on the enum: `dispatch` for impls of the traits
+and `from` for `From` of the variant types are generated by default, listing any of them limits generated items to the listed ones.
+`try_from` for `TryFrom` back to the variant types, `as_ref` for `TryFrom` of references along with `as_variant` accessors,
+`variant_names` for [names of the variants](#variant-names) and `discriminant_index` for their indexes are generated only if listed.
+`TryFrom` is left out by default, since it conflicts with the blanket impl of `core` for types converted with `Into`,
+e.g. for `Box<Self>` variant of a recursive enum
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Amount {
+        fn cents(&self) -> u32;
+    }
+
+    #[dispatch(generate(dispatch, try_from))]
+    pub enum Price {
+        Cents(u32),
+        Free(Free),
+    }
+);
+
+pub struct Free;
+
+impl Amount for u32 {
+    fn cents(&self) -> u32 {
+        *self
+    }
+}
+impl Amount for Free {
+    fn cents(&self) -> u32 {
+        0
+    }
+}
+
+// `From<u32>` isn't generated, so it can be implemented by hand
+impl From<u32> for Price {
+    fn from(cents: u32) -> Self {
+        if cents == 0 { Price::Free(Free) } else { Price::Cents(cents) }
+    }
+}
+
+assert_eq!(Price::from(150).cents(), 150);
+assert!(u32::try_from(Price::from(0)).is_err());
+assert_eq!(u32::try_from(Price::Cents(5)).ok(), Some(5));
+```
+
+Without `dispatch` only conversions are generated, variants don't have to implement the trait
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Amount {
+        fn cents(&self) -> u32;
+    }
+
+    #[dispatch(generate(from, as_ref))]
+    pub enum Value {
+        Number(i64),
+        Text(String),
+    }
+);
+
+let mut value = Value::from("a".to_string());
+value.as_variant_mut::<String>().unwrap().push('b');
+assert_eq!(value.as_variant::<String>().unwrap(), "ab");
+assert!(value.as_variant::<i64>().is_none());
+```
+
+Opt-in items alone are added to the default ones
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Amount {
+        fn cents(&self) -> u32;
+    }
+
+    #[dispatch(generate(as_ref))]
+    pub enum Price {
+        Cents(u32),
+    }
+);
+
+impl Amount for u32 {
+    fn cents(&self) -> u32 {
+        *self
+    }
+}
+
+let mut price = Price::from(5);
+*price.as_variant_mut::<u32>().unwrap() += 1;
+assert_eq!(price.cents(), 6);
+```
+
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Amount {
+        fn cents(&self) -> u32;
+    }
+
+    pub enum Price {
+        Cents(u32),
+    }
+);
+
+impl Amount for u32 {
+    fn cents(&self) -> u32 {
+        *self
+    }
+}
+
+// error: no method named `as_variant` found for enum `Price`
+assert!(Price::from(5).as_variant::<u32>().is_some());
+```
+
+Unknown items are rejected
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Amount {
+        fn cents(&self) -> u32;
+    }
+
+    #[dispatch(generate(dispatch, into))]
+    pub enum Price {
+        Cents(u32),
+    }
+);
+```
+
+## Default variant
+Variant marked with `#[dispatch(default)]` is used for generated `Default` implementation of the enum,
+its fields are created with `Default::default()`, only one variant can be marked
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Compress {
+        fn level(&self) -> u32;
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum AnyCompression {
+        Stored,
+        #[dispatch(default)]
+        Zstd(Zstd),
+    }
+);
+
+pub struct Stored;
+#[derive(Debug, PartialEq, Default)]
+pub struct Zstd {
+    level: u32,
+}
+
+impl Compress for Stored {
+    fn level(&self) -> u32 {
+        0
+    }
+}
+
+impl Compress for Zstd {
+    fn level(&self) -> u32 {
+        self.level
+    }
+}
+
+assert_eq!(AnyCompression::default(), AnyCompression::Zstd(Zstd { level: 0 }));
+```
+
+## Generic enums
+Enum can have generic parameters and `where` clause, they are copied to the generated impls.
+`TryFrom` can't be implemented for a bare type parameter, so with `generate(try_from)` such variants need `#[dispatch(no_try_from)]`,
+it keeps `From` and leaves out `TryFrom` of the value and of references to it
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Count {
+        fn count(&self) -> usize;
+    }
+
+    #[dispatch(generate(try_from))]
+    pub enum Container<T> where T: Count {
+        List(Vec<T>),
+        #[dispatch(no_try_from)]
+        One(T),
+    }
+);
+
+pub struct Item;
+
+impl Count for Item {
+    fn count(&self) -> usize {
+        1
+    }
+}
+impl<T: Count> Count for Vec<T> {
+    fn count(&self) -> usize {
+        self.iter().map(Count::count).sum()
+    }
+}
+
+// `Vec<Item>` converts into both `Container<Item>` and `Container<Vec<Item>>`, so the parameter is named
+assert_eq!(Container::<Item>::from(vec![Item, Item]).count(), 2);
+assert_eq!(Container::from(Item).count(), 1);
+assert!(Vec::try_from(Container::from(Item)).is_err());
+```
+
+Lifetime parameters work the same way, so variants can hold references, conversions borrow for the lifetime of the enum
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Size {
+        fn size(&self) -> usize;
+    }
+
+    #[dispatch(generate(try_from, as_ref))]
+    pub enum View<'a> {
+        Borrowed(&'a Data),
+        Owned(Data),
+        Text(&'a str),
+    }
+);
+
+pub struct Data(Vec<u8>);
+
+impl Size for Data {
+    fn size(&self) -> usize {
+        self.0.len()
+    }
+}
+impl Size for &Data {
+    fn size(&self) -> usize {
+        self.0.len()
+    }
+}
+impl Size for &str {
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+let data = Data(vec![1, 2]);
+let view = View::from(&data);
+assert_eq!(view.size(), 2);
+assert_eq!(View::from("abc").size(), 3);
+assert_eq!(View::from(Data(vec![1])).size(), 1);
+assert!(view.as_variant::<&Data>().is_some());
+let borrowed: &Data = View::from(&data).try_into().ok().unwrap();
+assert_eq!(borrowed.0, [1, 2]);
+```
+
+## Generic traits
+Trait can have generic parameters, they are declared on the generated impl together with the parameters of the enum,
+so parameters of the trait and the enum should have different names.
+`with trait Name<T, U = Type>;` after the trait body instead binds type parameters of the trait to the parameters
+of the enum with the same name or to concrete types, so the enum implements only this instance of the trait
+```
+use declarative_enum_dispatch::enum_dispatch;
+use std::fmt::Write;
+
+enum_dispatch!(
+    pub trait Serialize<W: Write> {
+        fn serialize(&self, writer: &mut W) -> std::fmt::Result;
+    }
+
+    pub enum Value {
+        Number(Number),
+        Text(Text),
+    }
+);
+
+pub struct Number(i32);
+pub struct Text(String);
+
+impl<W: Write> Serialize<W> for Number {
+    fn serialize(&self, writer: &mut W) -> std::fmt::Result {
+        write!(writer, "{}", self.0)
+    }
+}
+impl<W: Write> Serialize<W> for Text {
+    fn serialize(&self, writer: &mut W) -> std::fmt::Result {
+        write!(writer, "{:?}", self.0)
+    }
+}
+
+let mut out = String::new();
+Value::from(Number(1)).serialize(&mut out).unwrap();
+Value::from(Text("a".to_string())).serialize(&mut out).unwrap();
+assert_eq!(out, "1\"a\"");
+```
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Count<T> {
+        fn count(&self, item: &T) -> usize;
+    }
+    with trait Count<T>;
+
+    pub enum Container<T: PartialEq> {
+        Vec(Vec<T>),
+        Option(Option<T>),
+    }
+);
+
+impl<T: PartialEq> Count<T> for Vec<T> {
+    fn count(&self, item: &T) -> usize {
+        self.iter().filter(|value| *value == item).count()
+    }
+}
+impl<T: PartialEq> Count<T> for Option<T> {
+    fn count(&self, item: &T) -> usize {
+        self.iter().filter(|value| *value == item).count()
+    }
+}
+
+assert_eq!(Container::from(vec![1, 2, 1]).count(&1), 2);
+assert_eq!(Container::from(Some('a')).count(&'b'), 0);
+```
+
+## Multiple traits
+Several traits can be dispatched by the same enum, enum and conversions are generated once.
+Associated types of a trait are bound right after its body.
+Methods are called with fully qualified path, so traits can have methods with the same name.
+Methods of a single trait are called with method syntax instead, so variant types wrapping the implementing type,
+e.g. `Box<Rect>` or `Arc<Rect>`, are dereferenced to it
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> u32;
+    }
+    pub trait Named {
+        type Name;
+        fn name(&self) -> Self::Name;
+    }
+    with type Name = &'static str;
+
+    pub enum Shape {
+        Square(Square),
+        Point,
+    }
+);
+
+pub struct Square(u32);
+pub struct Point;
+
+impl Square {
+    fn area(&self) -> u64 {
+        unreachable!("inherent method is not called")
+    }
+}
+impl Area for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+impl Area for Point {
+    fn area(&self) -> u32 {
+        0
+    }
+}
+impl Named for Square {
+    type Name = &'static str;
+    fn name(&self) -> &'static str {
+        "square"
+    }
+}
+impl Named for Point {
+    type Name = &'static str;
+    fn name(&self) -> &'static str {
+        "point"
+    }
+}
+
+let square = Shape::from(Square(3));
+assert_eq!(square.area(), 9);
+assert_eq!(square.name(), "square");
+assert_eq!(Shape::Point.name(), "point");
+```
+
+## Multiple enums
+Traits can be followed by several enums, every enum gets its own dispatch, conversions and options,
+while traits are declared once
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Volume {
+        fn volume(&self) -> f32;
+    }
+
+    #[dispatch(generate(variant_names))]
+    pub enum Shape2D {
+        Square(Square),
+        Circle(Circle),
+    }
+
+    #[dispatch(partial_eq)]
+    pub enum Shape3D {
+        Cube(Cube),
+    }
+    extra impl {
+        pub fn side(&self) -> f32 {
+            self.volume().cbrt()
+        }
+    }
+);
+
+pub struct Square(f32);
+pub struct Circle(f32);
+#[derive(PartialEq)]
+pub struct Cube(f32);
+
+impl Volume for Square {
+    fn volume(&self) -> f32 {
+        0.0
+    }
+}
+
+impl Volume for Circle {
+    fn volume(&self) -> f32 {
+        0.0
+    }
+}
+
+impl Volume for Cube {
+    fn volume(&self) -> f32 {
+        self.0 * self.0 * self.0
+    }
+}
+
+assert_eq!(Shape2D::from(Square(2.0)).volume(), 0.0);
+assert_eq!(Shape3D::from(Cube(2.0)).volume(), 8.0);
+assert_eq!(Shape3D::from(Cube(2.0)).side(), 2.0);
+assert!(Shape3D::from(Cube(1.0)) == Shape3D::from(Cube(1.0)));
+assert_eq!(Shape2D::VARIANTS, &["Square", "Circle"]);
+```
+
+## Existing enum
+Enum declared with `existing enum` is not emitted, only dispatch, conversions and helpers are generated for it.
+Variants have to be listed the same way as in the declaration
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+#[derive(Debug, PartialEq)]
+pub enum Event {
+    Click(Click),
+    Key(Key),
+}
+
+enum_dispatch!(
+    pub trait Handle {
+        fn handle(&self) -> &'static str;
+    }
+
+    pub existing enum Event {
+        Click(Click),
+        Key(Key),
+    }
+);
+
+#[derive(Debug, PartialEq)]
+pub struct Click;
+#[derive(Debug, PartialEq)]
+pub struct Key;
+
+impl Handle for Click {
+    fn handle(&self) -> &'static str {
+        "click"
+    }
+}
+impl Handle for Key {
+    fn handle(&self) -> &'static str {
+        "key"
+    }
+}
+
+assert_eq!(Event::from(Key), Event::Key(Key));
+assert_eq!(Event::Click(Click).handle(), "click");
+```
+
+## Trait from another module
+Trait declared with `use trait path::Trait` is not emitted, its methods are listed only to generate dispatch.
+Generated impl refers to the trait by the given path, so it doesn't have to be imported
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+mod traits {
+    pub trait Area {
+        fn area(&self) -> f32;
+        fn scaled(&self, factor: f32) -> f32 {
+            self.area() * factor
+        }
+    }
+}
+
+enum_dispatch!(
+    #[dispatch(as_dyn)]
+    use trait traits::Area {
+        fn area(&self) -> f32;
+        fn scaled(&self, factor: f32) -> f32;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+pub struct Square(f32);
+
+impl traits::Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+use traits::Area;
+
+let shape = AnyShape::from(Square(2.0));
+assert_eq!(shape.area(), 4.0);
+assert_eq!(shape.scaled(2.0), 8.0);
+assert_eq!(shape.as_dyn().area(), 4.0);
+```
+
+Path can be relative or start with `crate`, `self`, `super` or `::`, so traits of other crates can be dispatched as well
+```
+use std::fmt::Write;
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    use trait ::core::fmt::Write {
+        fn write_str(&mut self, s: &str) -> ::core::fmt::Result;
+    }
+
+    pub enum AnyWriter {
+        Text(String),
+    }
+);
+
+let mut writer = AnyWriter::from(String::new());
+write!(writer, "{}-{}", 1, 2).unwrap();
+assert!(matches!(writer, AnyWriter::Text(text) if text == "1-2"));
+```
+
+## Variant types from other modules
+Variant types can be paths, so types with the same name from different modules are dispatched and converted as usual
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+pub mod db {
+    pub trait Connection {
+        fn url(&self) -> String;
+    }
+
+    pub mod postgres {
+        pub struct Conn(pub u16);
+
+        impl super::Connection for Conn {
+            fn url(&self) -> String {
+                format!("postgres://localhost:{}", self.0)
+            }
+        }
+    }
+
+    pub mod sqlite {
+        pub struct Conn(pub &'static str);
+
+        impl super::Connection for Conn {
+            fn url(&self) -> String {
+                format!("sqlite://{}", self.0)
+            }
+        }
+    }
+}
+
+enum_dispatch!(
+    use trait db::Connection {
+        fn url(&self) -> String;
+    }
+
+    pub enum Backend {
+        Postgres(db::postgres::Conn),
+        Sqlite(db::sqlite::Conn),
+    }
+);
+
+use db::Connection;
+
+assert_eq!(Backend::from(db::postgres::Conn(5432)).url(), "postgres://localhost:5432");
+assert_eq!(Backend::from(db::sqlite::Conn("app.db")).url(), "sqlite://app.db");
+```
+
+## Fallback arm
+Match in the generated methods is exhaustive, so every variant has to be listed.
+`#[dispatch(fallback)]` on the enum adds a wildcard arm that panics, e.g. to leave out hidden variants of `existing` enum.
+Otherwise `#[non_exhaustive]` and other attributes are copied to the enum as usual
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+#[non_exhaustive]
+pub enum Command {
+    Run(Run),
+    #[doc(hidden)]
+    Internal,
+}
+
+enum_dispatch!(
+    pub trait Execute {
+        fn execute(&self) -> u8;
+    }
+
+    #[dispatch(fallback)]
+    pub existing enum Command {
+        Run(Run),
+    }
+);
+
+pub struct Run;
+
+impl Execute for Run {
+    fn execute(&self) -> u8 {
+        1
+    }
+}
+
+assert_eq!(Command::from(Run).execute(), 1);
+let result = std::panic::catch_unwind(|| Command::Internal.execute());
+assert!(result.is_err());
+```
+
+## Unsafe methods
+Unsafe methods are dispatched from `unsafe fn` with the call wrapped into `unsafe` block
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait RawBuffer {
+        unsafe fn first(&self) -> u8;
+        async unsafe fn first_async(&mut self) -> u8;
+    }
+
+    pub enum Buffer {
+        Heap(Heap),
+    }
+);
+
+pub struct Heap(Vec<u8>);
+
+impl RawBuffer for Heap {
+    unsafe fn first(&self) -> u8 {
+        *self.0.as_ptr()
+    }
+    async unsafe fn first_async(&mut self) -> u8 {
+        self.first()
+    }
+}
+
+let buffer = Buffer::from(Heap(vec![7]));
+assert_eq!(unsafe { buffer.first() }, 7);
+```
+
+## Const methods
+`const fn` methods are forwarded with `const` kept on the generated method.
+Stable Rust doesn't allow `const fn` in traits yet, so this needs a compiler with const trait support
+```ignore
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Dimension {
+        const fn dimension(&self) -> usize;
+    }
+
+    pub enum Space {
+        Plane(Plane),
+    }
+);
+```
+
+## Associated functions
+Associated functions without `self` have no value to dispatch on,
+they are forwarded to the type chosen with `#[dispatch(associated = Type)]` on the enum
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Codec {
+        fn default_name() -> String;
+        fn with_level<const L: u8>(input: &[u8]) -> usize;
+        fn encode(&self, input: &[u8]) -> usize;
+    }
+
+    #[dispatch(associated = Plain)]
+    pub enum AnyCodec {
+        Plain(Plain),
+    }
+);
+
+pub struct Plain;
+
+impl Codec for Plain {
+    fn default_name() -> String {
+        "plain".to_string()
+    }
+    fn with_level<const L: u8>(input: &[u8]) -> usize {
+        input.len() * L as usize
+    }
+    fn encode(&self, input: &[u8]) -> usize {
+        input.len()
+    }
+}
+
+assert_eq!(AnyCodec::default_name(), "plain");
+assert_eq!(AnyCodec::with_level::<2>(b"ab"), 4);
+assert_eq!(AnyCodec::from(Plain).encode(b"abc"), 3);
+```
+
+Without it associated function is an error
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Codec {
+        fn default_name() -> String;
+    }
+
+    pub enum AnyCodec {
+        Plain(Plain),
+    }
+);
+# pub struct Plain;
+```
+
+## Attributes of generated methods
+Attributes of the trait method are copied to the generated method, except doc comments that stay on the trait only.
+Attributes that make sense only for the generated method, like `#[inline(always)]`, can be wrapped into `#[dispatch(attr(...))]`,
+they are removed from the trait declaration.
+Generated methods allow `clippy::too_many_arguments` and `clippy::type_complexity`, the same lints of the trait declaration
+are allowed with attributes of the trait method
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Hash {
+        #[dispatch(attr(inline(always)))]
+        fn hash(&self) -> u64;
+    }
+
+    pub enum AnyHash {
+        Fnv(Fnv),
+    }
+);
+
+pub struct Fnv;
+
+impl Hash for Fnv {
+    fn hash(&self) -> u64 {
+        0xcbf29ce484222325
+    }
+}
+
+assert_eq!(AnyHash::from(Fnv).hash(), 0xcbf29ce484222325);
+```
+
+`inline` wrapped into `cfg_attr` replaces the default `#[inline]` only where its condition holds,
+e.g. `#[dispatch(attr(cfg_attr(feature = "fast", inline(always))))]`. `cfg_attr` written on the method stays on the trait too,
+so it can wrap `inline` only for provided methods
+```
+#![deny(unused_attributes)]
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Hash {
+        #[dispatch(attr(cfg_attr(all(), inline(always))))]
+        fn hash(&self) -> u64;
+        #[dispatch(attr(cfg_attr(any(), inline(never))))]
+        fn seed(&self) -> u64;
+        #[cfg_attr(all(), inline(always))]
+        fn mixed(&self) -> u64 {
+            self.hash() ^ self.seed()
+        }
+    }
+
+    pub enum AnyHash {
+        Fnv(Fnv),
+    }
+);
+
+pub struct Fnv;
+
+impl Hash for Fnv {
+    fn hash(&self) -> u64 {
+        0xcbf29ce484222325
+    }
+    fn seed(&self) -> u64 {
+        0
+    }
+}
+
+assert_eq!(AnyHash::from(Fnv).mixed(), 0xcbf29ce484222325);
+```
+
+So `#[must_use]` of the trait method warns about unused results of calls through the enum too
+```compile_fail
+#![deny(unused_must_use)]
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Shape {
+        #[must_use = "area is computed, not stored"]
+        fn area(&self) -> f32;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+pub struct Square(f32);
+
+impl Shape for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+AnyShape::from(Square(2.0)).area();
+```
+
+`#[deprecated]` stays on the trait method, calls through the enum resolve to it and are reported,
+while the generated method calling the deprecated method of the variant allows the warning, so declaring the enum doesn't warn
+```
+#![deny(deprecated)]
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Shape {
+        #[deprecated(note = "use `area`")]
+        fn size(&self) -> f32;
+        fn area(&self) -> f32;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+pub struct Square(f32);
+
+impl Shape for Square {
+    fn size(&self) -> f32 {
+        self.area()
+    }
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+assert_eq!(AnyShape::from(Square(2.0)).area(), 4.0);
+```
+```compile_fail
+#![deny(deprecated)]
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Shape {
+        #[deprecated(note = "use `area`")]
+        fn size(&self) -> f32;
+        fn area(&self) -> f32;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+pub struct Square(f32);
+
+impl Shape for Square {
+    fn size(&self) -> f32 {
+        self.area()
+    }
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+// error: use of deprecated method `Shape::size`: use `area`
+AnyShape::from(Square(2.0)).size();
+```
+
+## Attributes of the generated impl
+`#[dispatch(attr(...))]` on the trait is applied to the generated `impl` instead of the trait,
+e.g. to allow calls of deprecated methods in the generated code.
+Attribute macros see the generated methods as macro calls, so they can't rewrite them,
+`#[async_trait]` is replaced by `#[dispatch(async_trait)]` on the trait, see [async-trait](#async-trait)
+```
+#![deny(deprecated)]
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    #[dispatch(attr(allow(deprecated)))]
+    pub trait Area {
+        fn area(&self) -> f32;
+        #[deprecated = "use `area`"]
+        fn size(&self) -> f32 {
+            self.area()
+        }
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+pub struct Square(f32);
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+assert_eq!(AnyShape::from(Square(2.0)).area(), 4.0);
+```
+
+## Inlining
+Generated dispatch methods are marked `#[inline]`, so the `match` can be inlined across crates.
+Explicit `#[inline(...)]` or `#[dispatch(attr(inline(...)))]` on the trait method replaces the default one,
+`#[dispatch(no_inline)]` on the enum disables it for all methods
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Render {
+        fn render(&self) -> String;
+    }
+
+    #[dispatch(no_inline)]
+    pub enum AnyRender {
+        Text(Text),
+    }
+);
+
+pub struct Text(&'static str);
+
+impl Render for Text {
+    fn render(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+assert_eq!(AnyRender::from(Text("hi")).render(), "hi");
+```
+
+## Raw identifiers
+Methods and arguments can be named with raw identifiers
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Token {
+        fn r#type(&self) -> &'static str;
+        fn r#match(&self, r#in: &str) -> bool;
+    }
+
+    pub enum AnyToken {
+        Keyword(Keyword),
+    }
+);
+
+pub struct Keyword(&'static str);
+
+impl Token for Keyword {
+    fn r#type(&self) -> &'static str {
+        "keyword"
+    }
+    fn r#match(&self, r#in: &str) -> bool {
+        self.0 == r#in
+    }
+}
+
+let token = AnyToken::from(Keyword("fn"));
+assert_eq!(token.r#type(), "keyword");
+assert!(token.r#match("fn"));
+```
+Errors about such methods name them as written, e.g. associated function `r#async` without `#[dispatch(associated = Type)]`
+is reported as "method `fn r#async` should receive self"
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Token {
+        fn r#async() -> bool;
+    }
+
+    pub enum AnyToken {
+        Keyword(Keyword),
+    }
+);
+
+pub struct Keyword;
+
+impl Token for Keyword {
+    fn r#async() -> bool {
+        true
+    }
+}
+```
+
+## Mutable arguments
+Default implementations can take arguments by `mut` binding, generated method just forwards them
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Encoder {
+        fn encode(&self, mut buf: Vec<u8>, byte: u8) -> Vec<u8> {
+            buf.push(byte);
+            buf
+        }
+    }
+
+    pub enum AnyEncoder {
+        Raw(Raw),
+    }
+);
+
+pub struct Raw;
+
+impl Encoder for Raw {}
+
+assert_eq!(AnyEncoder::from(Raw).encode(vec![1], 2), vec![1, 2]);
+```
+
+## Pattern arguments
+Default implementations can destructure arguments, generated method receives them under a fresh name and forwards as is
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Board {
+        fn size(&self) -> i32;
+        fn contains(&self, (x, y): (i32, i32), _: bool) -> bool {
+            (0..self.size()).contains(&x) && (0..self.size()).contains(&y)
+        }
+    }
+
+    pub enum AnyBoard {
+        Chess(Chess),
+    }
+);
+
+pub struct Chess;
+
+impl Board for Chess {
+    fn size(&self) -> i32 {
+        8
+    }
+}
+
+let board = AnyBoard::from(Chess);
+assert!(board.contains((7, 0), true));
+assert!(!board.contains((8, 0), false));
+```
+
+## Unnamed arguments
+Arguments can be declared with `_`, generated method names them itself to forward the values
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+pub struct Event(&'static str);
+
+enum_dispatch!(
+    pub trait Listener {
+        fn notify(&self, _: Event, _: u32) -> String;
+    }
+
+    pub enum AnyListener {
+        Logger(Logger),
+    }
+);
+
+pub struct Logger;
+
+impl Listener for Logger {
+    fn notify(&self, event: Event, _: u32) -> String {
+        format!("got {}", event.0)
+    }
+}
+
+assert_eq!(AnyListener::from(Logger).notify(Event("click"), 0), "got click");
+```
+Anonymous arguments without pattern are not supported
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+pub struct Event;
+
+enum_dispatch!(
+    pub trait Listener {
+        fn notify(&self, Event);
+    }
+
+    pub enum AnyListener {
+        Logger(Logger),
+    }
+);
+
+pub struct Logger;
+```
+
+## Trailing commas
+Receiver can be followed by a trailing comma without other arguments, as generated by some formatters and macros
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Named {
+        fn name(&self,) -> String;
+    }
+
+    pub enum AnyNamed {
+        User(User),
+    }
+);
+
+pub struct User(String);
+
+impl Named for User {
+    fn name(&self) -> String {
+        self.0.clone()
+    }
+}
+
+assert_eq!(AnyNamed::from(User("bob".to_string())).name(), "bob");
+```
+
+## Many arguments
+There is no limit on the number of arguments, they are passed to the variant in the same order
+
+## Long traits
+Methods returning a type by name, e.g. `u32`, `Option<Self>` or `&str`, are built several per step,
+so traits with hundreds of them fit into the default `recursion_limit`.
+Generic methods, methods with `where` clause and other return types, e.g. `&mut T`, take a step each
+
+## Missing implementations
+Every variant type is checked to implement the trait, so the error names the type instead of pointing to generated methods
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+        // error: the trait `Area` is not implemented for `Circle`
+        Circle(Circle),
+    }
+);
+
+pub struct Square(f32);
+pub struct Circle(f32);
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+```
+
+Supertraits are checked the same way, so a variant type missing one of them is reported at the variant
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+use std::fmt::Debug;
+
+enum_dispatch!(
+    pub trait Area: Clone + Debug {
+        fn area(&self) -> f32;
+    }
+
+    #[derive(Clone, Debug)]
+    pub enum AnyShape {
+        Square(Square),
+        // error: the trait bound `Rect: Clone` is not satisfied
+        Rect(Rect),
+    }
+);
+
+#[derive(Clone, Debug)]
+pub struct Square(f32);
+#[derive(Debug)]
+pub struct Rect(f32, f32);
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+impl Area for Rect {
+    fn area(&self) -> f32 {
+        self.0 * self.1
+    }
+}
+```
+
+Types that are not in scope are reported at the variant as well
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    pub enum AnyShape {
+        // error: cannot find type `Cube` in this scope
+        Cube(Cube),
+    }
+);
+```
+
+`#[dispatch(check_derives)]` checks the variant fields against `Clone`, `Debug`, `PartialEq` and `Hash` derived on the enum,
+so a field type missing one of them is reported once at the field. Other derives are left to the compiler
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash)]
+    #[dispatch(check_derives)]
+    pub enum AnyShape<'a> {
+        Square(Square),
+        Labeled(#[dispatch] Square, &'a str),
+        #[cfg(any())]
+        Cube(Cube),
+        Empty,
+    }
+);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Square(u32);
+pub struct Empty;
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        (self.0 * self.0) as f32
+    }
+}
+
+impl Area for Empty {
+    fn area(&self) -> f32 {
+        0.0
+    }
+}
+
+let shape = AnyShape::Labeled(Square(2), "big");
+assert_eq!(shape.clone(), shape);
+assert_eq!(shape.area(), 4.0);
+```
+
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    #[derive(Clone, Debug)]
+    #[dispatch(check_derives)]
+    pub enum AnyShape {
+        // error: the trait bound `Square: Clone` is not satisfied
+        Square(Square),
+    }
+);
+
+#[derive(Debug)]
+pub struct Square(u32);
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        (self.0 * self.0) as f32
+    }
+}
+```
+
+Enums with type or const parameters are not supported, their bounds depend on the derive
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    #[derive(Clone)]
+    #[dispatch(check_derives)]
+    // error: `check_derives` doesn't support enum `AnyShape` with type or const parameters
+    pub enum AnyShape<T: Area> {
+        Square(T),
+    }
+);
+```
+
+## Where clause of the trait
+`where` clause of the trait is kept on the trait and repeated on the generated impl, `Self` there is the enum
+```
+use declarative_enum_dispatch::enum_dispatch;
+use std::fmt::Debug;
+
+enum_dispatch!(
+    pub trait Encode<T> where Self: Sized, T: Debug {
+        fn encode(self, tag: T) -> String;
+    }
+
+    pub enum AnyEncode {
+        Text(Text),
+    }
+);
+
+pub struct Text(&'static str);
+
+impl<T: Debug> Encode<T> for Text {
+    fn encode(self, tag: T) -> String {
+        format!("{tag:?}: {}", self.0)
+    }
+}
+
+assert_eq!(AnyEncode::from(Text("hi")).encode(1), "1: hi");
+```
+
+## Trait objects
+`#[dispatch(as_dyn)]` on the trait generates `as_dyn` method returning the inner value as `&dyn Trait`,
+`as_dyn_mut` returning it as `&mut dyn Trait` and `into_dyn` consuming the enum into `Box<dyn Trait>`.
+Names of the methods can be set with `#[dispatch(as_dyn = name)]`, `#[dispatch(as_dyn_mut = name)]` and `#[dispatch(into_dyn = name)]`
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    #[dispatch(as_dyn)]
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+pub struct Square(f32);
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+fn total(shapes: &[&dyn Area]) -> f32 {
+    shapes.iter().map(|shape| shape.area()).sum()
+}
+
+let shape = AnyShape::from(Square(2.0));
+assert_eq!(total(&[shape.as_dyn(), &Square(1.0)]), 5.0);
+```
+The trait has to be dyn compatible, so methods returning `Self`, generic ones or taking `impl Trait` need `where Self: Sized`,
+otherwise `dyn Trait` in the generated methods is rejected with E0038
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    #[dispatch(as_dyn)]
+    pub trait Area {
+        fn area(&self) -> f32;
+        // error[E0038]: the trait `Area` is not dyn compatible
+        fn scaled(&self, factor: f32) -> Self;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+#[derive(Clone, Copy)]
+pub struct Square(f32);
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+    fn scaled(&self, factor: f32) -> Self {
+        Square(self.0 * factor)
+    }
+}
+```
+Mutable trait object is handed out while the enum stays the owner of the value.
+Owned one boxes the inner value and lives as long as the enum could, so variants don't have to be `'static`.
+Variants with `via` accessor may reach the value through a shared reference only, so `as_dyn_mut` and `into_dyn` aren't generated for such enums.
+Unit variants have no value to borrow mutably, so `as_dyn_mut` isn't generated for enums with them
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    #[dispatch(as_dyn = as_scalable)]
+    #[dispatch(as_dyn_mut = as_scalable_mut)]
+    #[dispatch(into_dyn = into_scalable)]
+    pub trait Scale {
+        fn size(&self) -> f32;
+        fn scale(&mut self, factor: f32);
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+        Dot(Dot),
+    }
+);
+
+pub struct Square(f32);
+pub struct Dot;
+
+impl Scale for Square {
+    fn size(&self) -> f32 {
+        self.0
+    }
+    fn scale(&mut self, factor: f32) {
+        self.0 *= factor;
+    }
+}
+
+impl Scale for Dot {
+    fn size(&self) -> f32 {
+        0.0
+    }
+    fn scale(&mut self, _factor: f32) {}
+}
+
+fn double(shape: &mut dyn Scale) {
+    shape.scale(2.0);
+}
+
+let mut shape = AnyShape::from(Square(1.5));
+double(shape.as_scalable_mut());
+assert_eq!(shape.as_scalable().size(), 3.0);
+
+let mut dot = AnyShape::from(Dot);
+double(dot.as_scalable_mut());
+assert_eq!(dot.size(), 0.0);
+
+let mut registry: Vec<Box<dyn Scale>> = Vec::new();
+registry.push(shape.into_scalable());
+registry.push(dot.into_scalable());
+assert_eq!(registry.iter().map(|shape| shape.size()).sum::<f32>(), 3.0);
+```
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    #[dispatch(as_dyn)]
+    pub trait Scale {
+        fn scale(&mut self, factor: f32);
+    }
+
+    pub enum AnyShape {
+        Dot,
+    }
+);
+
+pub struct Dot;
+
+impl Scale for Dot {
+    fn scale(&mut self, _factor: f32) {}
+}
+
+// error: no method named `as_dyn_mut` found
+AnyShape::Dot.as_dyn_mut().scale(2.0);
+```
+
+## Returning `Self`
+Method returning `Self` returns the value of the variant type, so it is wrapped back into the same variant,
+unit variants are rebuilt as is and associated functions are converted with `From`
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Scale {
+        fn scaled(&self, scale: f32) -> Self;
+        fn unit() -> Self;
+    }
+
+    #[dispatch(associated = Square)]
+    #[derive(Debug, PartialEq)]
+    pub enum AnyShape {
+        Square(Square),
+        Dot,
+    }
+);
+
+#[derive(Debug, PartialEq)]
+pub struct Square(f32);
+#[derive(Debug, PartialEq)]
+pub struct Dot;
+
+impl Scale for Square {
+    fn scaled(&self, scale: f32) -> Self {
+        Square(self.0 * scale)
+    }
+    fn unit() -> Self {
+        Square(1.0)
+    }
+}
+
+impl Scale for Dot {
+    fn scaled(&self, _: f32) -> Self {
+        Dot
+    }
+    fn unit() -> Self {
+        Dot
+    }
+}
+
+assert_eq!(AnyShape::from(Square(2.0)).scaled(1.5), AnyShape::Square(Square(3.0)));
+assert_eq!(AnyShape::Dot.scaled(1.5), AnyShape::Dot);
+assert_eq!(AnyShape::unit(), AnyShape::Square(Square(1.0)));
+```
+
+Methods taking `self` by value rebuild the variant from the returned value the same way, so typestate transitions can be chained on the enum
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Step {
+        fn advance(self) -> Self where Self: Sized;
+        fn steps(&self) -> u32;
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum Machine {
+        Counting(Counting),
+        Stopped,
+    }
+);
+
+#[derive(Debug, PartialEq)]
+pub struct Counting(u32);
+#[derive(Debug, PartialEq)]
+pub struct Stopped;
+
+impl Step for Counting {
+    fn advance(self) -> Self {
+        Counting(self.0 + 1)
+    }
+    fn steps(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Step for Stopped {
+    fn advance(self) -> Self {
+        self
+    }
+    fn steps(&self) -> u32 {
+        0
+    }
+}
+
+let machine = Machine::from(Counting(0)).advance().advance();
+assert_eq!(machine, Machine::Counting(Counting(2)));
+assert_eq!(machine.advance().steps(), 3);
+assert_eq!(Machine::Stopped.advance().advance(), Machine::Stopped);
+```
+Variant with several fields can't be rebuilt from its dispatched field
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Scale {
+        fn scaled(&self, scale: f32) -> Self;
+    }
+
+    pub enum AnyShape {
+        Square(#[dispatch] Square, u32),
+    }
+);
+
+pub struct Square(f32);
+
+impl Scale for Square {
+    fn scaled(&self, scale: f32) -> Self {
+        Square(self.0 * scale)
+    }
+}
+```
+
+`Self` is also rebuilt inside of `Option<Self>`, `Result<Self, E>` and `Box<Self>`.
+Return type is recognized by its tokens, so wrapper has to be written exactly like this,
+paths like `std::option::Option<Self>`, type aliases and other wrappers are returned as is
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Grow {
+        fn grown(&self, by: f32) -> Option<Self> where Self: Sized;
+        fn validated(self) -> Result<Self, String> where Self: Sized;
+        fn boxed(&self) -> Box<Self>;
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+#[derive(Debug, PartialEq)]
+pub struct Square(f32);
+
+impl Grow for Square {
+    fn grown(&self, by: f32) -> Option<Self> {
+        (by > 0.0).then_some(Square(self.0 + by))
+    }
+    fn validated(self) -> Result<Self, String> {
+        if self.0 >= 0.0 { Ok(self) } else { Err("negative side".to_string()) }
+    }
+    fn boxed(&self) -> Box<Self> {
+        Box::new(Square(self.0))
+    }
+}
+
+let shape = AnyShape::from(Square(1.0));
+assert_eq!(shape.grown(1.0), Some(AnyShape::Square(Square(2.0))));
+assert_eq!(shape.grown(-1.0), None);
+assert_eq!(shape.boxed(), Box::new(AnyShape::Square(Square(1.0))));
+assert_eq!(shape.validated(), Ok(AnyShape::Square(Square(1.0))));
+assert!(AnyShape::from(Square(-1.0)).validated().is_err());
+```
+
+`Box<Self>` with `where Self: Sized` keeps the trait dyn compatible, the boxed variant value is moved into the boxed enum
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    #[dispatch(as_dyn)]
+    pub trait Shape {
+        fn area(&self) -> f32;
+        fn boxed(&self) -> Box<Self> where Self: Sized;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+        Dot,
+    }
+);
+
+pub struct Square(f32);
+pub struct Dot;
+
+impl Shape for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+    fn boxed(&self) -> Box<Self> {
+        Box::new(Square(self.0))
+    }
+}
+
+impl Shape for Dot {
+    fn area(&self) -> f32 {
+        0.0
+    }
+    fn boxed(&self) -> Box<Self> {
+        Box::new(Dot)
+    }
+}
+
+let shape = AnyShape::from(Square(2.0));
+let boxed: Box<AnyShape> = shape.boxed();
+assert!(matches!(*boxed, AnyShape::Square(Square(side)) if side == 2.0));
+assert!(matches!(*AnyShape::Dot.boxed(), AnyShape::Dot));
+let shapes: Vec<&dyn Shape> = vec![shape.as_dyn(), &*boxed];
+assert_eq!(shapes.iter().map(|shape| shape.area()).sum::<f32>(), 8.0);
+```
+
+## Variant names
+With `#[dispatch(generate(variant_names))]` `VARIANTS` constant lists names of the variants and `variant_name()` returns name of the current one,
+variants disabled with `#[cfg(...)]` are left out.
+With `discriminant_index` listed `discriminant_index()` returns position of the current variant in declaration, it counts disabled variants too,
+so indexes stay the same in every configuration and can key lookup tables, but don't always match positions in `VARIANTS`
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    #[dispatch(generate(variant_names, discriminant_index))]
+    pub enum AnyShape {
+        Square(Square),
+        #[cfg(any())]
+        Hidden(Square),
+        Dot,
+    }
+);
+
+pub struct Square(f32);
+pub struct Dot;
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+impl Area for Dot {
+    fn area(&self) -> f32 {
+        0.0
+    }
+}
+
+assert_eq!(AnyShape::VARIANTS, &["Square", "Dot"]);
+assert_eq!(AnyShape::from(Square(1.0)).variant_name(), "Square");
+assert_eq!(AnyShape::Dot.variant_name(), "Dot");
+assert_eq!(AnyShape::from(Square(1.0)).discriminant_index(), 0);
+assert_eq!(AnyShape::Dot.discriminant_index(), 2);
+```
+
+Default methods of the trait are compiled for every variant type, so they can't call `variant_name()` of the enum directly.
+The enum also implements [`VariantName`] trait, a default method can require it with `where Self: VariantName`
+and be marked with `#[dispatch(skip)]`, then the enum runs the default body itself instead of dispatching to variant types,
+which don't implement `VariantName`
+```
+use declarative_enum_dispatch::{enum_dispatch, VariantName};
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+        #[dispatch(skip)]
+        fn describe(&self) -> String where Self: VariantName {
+            format!("{} of area {}", self.variant_name(), self.area())
+        }
+    }
+
+    #[dispatch(generate(variant_names))]
+    pub enum AnyShape {
+        Square(Square),
+        Dot,
+    }
+);
+
+pub struct Square(f32);
+pub struct Dot;
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+impl Area for Dot {
+    fn area(&self) -> f32 {
+        0.0
+    }
+}
+
+assert_eq!(AnyShape::from(Square(2.0)).describe(), "Square of area 4");
+assert_eq!(AnyShape::Dot.describe(), "Dot of area 0");
+```
+
+Inherent `variant_name()` takes precedence over a trait method of the same name, so without the opt-in
+such trait method is dispatched to the variants as any other one
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Named {
+        fn variant_name(&self) -> &'static str;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+pub struct Square;
+
+impl Named for Square {
+    fn variant_name(&self) -> &'static str {
+        "square"
+    }
+}
+
+assert_eq!(AnyShape::from(Square).variant_name(), "square");
+```
+
+## Visitor
+`#[dispatch(visitor = Name)]` on the enum generates trait `Name` with a method for every variant taking its value
+and `visit` method of the enum calling the one of the current variant.
+Methods are named with `#[dispatch(visit = name)]` on the variant, as `macro_rules!` can't build names like `visit_square`,
+variants without it give their own name to the method. Methods do nothing by default,
+so visitor implements only the variants it is interested in. Variants with `via` accessor can't be visited
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    #[dispatch(visitor = ShapeVisitor)]
+    pub enum AnyShape {
+        #[dispatch(visit = visit_square)]
+        Square(Square),
+        #[dispatch(visit = visit_circle)]
+        Circle(Circle),
+        #[dispatch(visit = visit_dot)]
+        Dot,
+    }
+);
+
+pub struct Square(f32);
+pub struct Circle(f32);
+pub struct Dot;
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+impl Area for Circle {
+    fn area(&self) -> f32 {
+        3.0 * self.0 * self.0
+    }
+}
+
+impl Area for Dot {
+    fn area(&self) -> f32 {
+        0.0
+    }
+}
+
+#[derive(Default)]
+struct Stats {
+    square_sides: f32,
+    dots: usize,
+}
+
+impl ShapeVisitor for Stats {
+    fn visit_square(&mut self, square: &Square) {
+        self.square_sides += 4.0 * square.0;
+    }
+    fn visit_dot(&mut self, _: &Dot) {
+        self.dots += 1;
+    }
+}
+
+let shapes = [AnyShape::from(Square(1.0)), AnyShape::from(Circle(1.0)), AnyShape::Dot];
+let mut stats = Stats::default();
+for shape in &shapes {
+    shape.visit(&mut stats);
+}
+assert_eq!(stats.square_sides, 4.0);
+assert_eq!(stats.dots, 1);
+```
+
+## Match macro
+`#[dispatch(match_macro = name)]` on the enum generates `name!(value, |inner| body)` macro matching every variant of `value`
+and running `body` with its value bound to `inner`, so code generic over the variant types doesn't list the variants.
+Values are bound the same way as in a `match`, unit variants bind a fresh unit struct and `via` accessors are applied to the bound value.
+The macro is declared with `macro_rules!`, so it can be used after the enum in the same module and its submodules,
+names used by the expansion, like the enum and the traits, have to be in scope
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    #[dispatch(match_macro = shape_match)]
+    pub enum AnyShape {
+        Square(Square),
+        Circle(Circle),
+        Dot,
+    }
+);
+
+pub struct Square(f32);
+pub struct Circle(f32);
+pub struct Dot;
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+impl Area for Circle {
+    fn area(&self) -> f32 {
+        3.0 * self.0 * self.0
+    }
+}
+
+impl Area for Dot {
+    fn area(&self) -> f32 {
+        0.0
+    }
+}
+
+fn doubled_area(shape: &AnyShape) -> f32 {
+    shape_match!(shape, |value| value.area() * 2.0)
+}
+
+assert_eq!(doubled_area(&AnyShape::from(Circle(1.0))), 6.0);
+assert_eq!(doubled_area(&AnyShape::Dot), 0.0);
+
+let boxed = shape_match!(AnyShape::from(Square(2.0)), |value| Box::new(value) as Box<dyn Area>);
+assert_eq!(boxed.area(), 4.0);
+```
+
+## Display
+`#[dispatch(display)]` on the enum implements `Display` by forwarding to the variant value,
+so a wrapper trait isn't needed, the formatter with its options is passed as is
+```
+use std::fmt;
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    #[dispatch(display)]
+    pub enum AnyShape {
+        Square(Square),
+        Dot,
+    }
+);
+
+pub struct Square(f32);
+pub struct Dot;
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+impl Area for Dot {
+    fn area(&self) -> f32 {
+        0.0
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "square {}", self.0)
+    }
+}
+
+impl fmt::Display for Dot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("dot")
+    }
+}
+
+assert_eq!(AnyShape::from(Square(2.0)).to_string(), "square 2");
+assert_eq!(format!("[{:>5}]", AnyShape::Dot), "[  dot]");
+```
+
+## Equality
+`#[dispatch(partial_eq)]` on the enum implements `PartialEq`, values of the same variant are compared, different variants are not equal.
+Unlike derived implementation, types of the values are bound instead of generic parameters,
+so the enum is comparable when parameters themselves aren't
+```
+use std::marker::PhantomData;
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    #[dispatch(partial_eq)]
+    pub enum AnyShape<Unit> {
+        Square(Square<Unit>),
+        Dot,
+    }
+);
+
+pub struct Square<Unit>(f32, PhantomData<Unit>);
+#[derive(PartialEq)]
+pub struct Dot;
+
+impl<Unit> PartialEq for Square<Unit> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<Unit> Area for Square<Unit> {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+impl Area for Dot {
+    fn area(&self) -> f32 {
+        0.0
+    }
+}
+
+pub struct Meters;
+
+let square = AnyShape::<Meters>::from(Square(1.0, PhantomData));
+assert!(square == AnyShape::from(Square(1.0, PhantomData)));
+assert!(square != AnyShape::from(Square(2.0, PhantomData)));
+assert!(square != AnyShape::Dot);
+assert!(AnyShape::<Meters>::Dot == AnyShape::Dot);
+```
+
+## Async methods
+Async methods can take arguments and return values with any receiver, the call is awaited in the generated method.
+Async methods with default implementation are forwarded too, so implementations of the variants can override them
+```
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+# struct Noop;
+# impl std::task::Wake for Noop {
+#     fn wake(self: std::sync::Arc<Self>) {}
+# }
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Storage {
+        async fn load(&mut self, n: usize) -> String;
+        async fn size(&self) -> usize;
+        async fn into_bytes(self) -> Vec<u8> where Self: Sized;
+        async fn describe(&self) -> String {
+            format!("{} bytes", self.size().await)
+        }
+    }
+
+    pub enum AnyStorage {
+        Memory(Memory),
+    }
+);
+
+pub struct Memory(String);
+
+impl Storage for Memory {
+    async fn load(&mut self, n: usize) -> String {
+        self.0 = self.0.repeat(n);
+        self.0.clone()
+    }
+    async fn size(&self) -> usize {
+        self.0.len()
+    }
+    async fn into_bytes(self) -> Vec<u8> {
+        self.0.into_bytes()
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(std::sync::Arc::new(Noop));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+let mut storage = AnyStorage::from(Memory("ab".to_string()));
+assert_eq!(block_on(storage.load(2)), "abab");
+assert_eq!(block_on(storage.size()), 4);
+assert_eq!(block_on(storage.describe()), "4 bytes");
+assert_eq!(block_on(storage.into_bytes()), b"abab");
+```
+
+Boxed futures with elided lifetime are returned by the variants as is
+```
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+# struct Noop;
+# impl std::task::Wake for Noop {
+#     fn wake(self: std::sync::Arc<Self>) {}
+# }
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Task {
+        fn run(&self) -> Pin<Box<dyn Future<Output = ()> + '_>>;
+        fn value(&self) -> Pin<Box<dyn Future<Output = u32> + Send + '_>>;
+    }
+
+    pub enum AnyTask {
+        Counter(Counter),
+    }
+);
+
+pub struct Counter(u32);
+
+impl Task for Counter {
+    fn run(&self) -> Pin<Box<dyn Future<Output = ()> + '_>> {
+        Box::pin(async {})
+    }
+    fn value(&self) -> Pin<Box<dyn Future<Output = u32> + Send + '_>> {
+        Box::pin(async { self.0 })
+    }
+}
+
+let task = AnyTask::from(Counter(3));
+let waker = Waker::from(std::sync::Arc::new(Noop));
+let mut cx = Context::from_waker(&waker);
+assert_eq!(task.run().as_mut().poll(&mut cx), Poll::Ready(()));
+assert_eq!(task.value().as_mut().poll(&mut cx), Poll::Ready(3));
+```
+
+## Boxed trait objects
+`#[dispatch(into_box)]` on the trait generates `From<Enum> for Box<dyn Trait>`, so the enum can be passed to code working with boxed trait objects.
+Like `as_dyn`, it requires the trait to be dyn compatible
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    #[dispatch(into_box)]
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+        Dot,
+    }
+);
+
+pub struct Square(f32);
+pub struct Dot;
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+impl Area for Dot {
+    fn area(&self) -> f32 {
+        0.0
+    }
+}
+
+let shapes: Vec<Box<dyn Area>> = vec![AnyShape::from(Square(2.0)).into(), AnyShape::Dot.into(), Box::new(Square(1.0))];
+assert_eq!(shapes.iter().map(|shape| shape.area()).sum::<f32>(), 5.0);
+```
+
+## Trait object arguments
+Arguments can be trait objects, including parenthesized ones with lifetime or auto trait bounds
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+pub trait Canvas {
+    fn draw(&self, name: &str) -> String;
+}
+
+enum_dispatch!(
+    pub trait Render {
+        fn render(&self, canvas: &(dyn Canvas + Send)) -> String;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+pub struct Square;
+
+impl Render for Square {
+    fn render(&self, canvas: &(dyn Canvas + Send)) -> String {
+        canvas.draw("square")
+    }
+}
+
+pub struct Svg;
+
+impl Canvas for Svg {
+    fn draw(&self, name: &str) -> String {
+        format!("<{name}/>")
+    }
+}
+
+assert_eq!(AnyShape::from(Square).render(&Svg), "<square/>");
+```
+
+## `impl Trait` arguments
+Every `impl Trait` argument is a separate anonymous type parameter, any number of them is forwarded as is,
+also together with named generic parameters
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> i32;
+        fn covers(&self, a: &impl Area, b: &impl Area) -> bool;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+pub struct Square(i32);
+
+impl Area for Square {
+    fn area(&self) -> i32 {
+        self.0 * self.0
+    }
+    fn covers(&self, a: &impl Area, b: &impl Area) -> bool {
+        self.area() >= a.area() + b.area()
+    }
+}
+
+let shape = AnyShape::from(Square(3));
+assert!(shape.covers(&Square(2), &AnyShape::from(Square(2))));
+```
+
+Closure types with arguments and return type are captured whole, so `impl Fn(i32) -> i32` and `impl FnMut(&mut i32)` work the same way
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Numbers {
+        fn map(&self, f: impl Fn(i32) -> i32) -> i32;
+    }
+
+    pub enum AnyNumbers {
+        List(List),
+    }
+);
+
+pub struct List(Vec<i32>);
+
+impl Numbers for List {
+    fn map(&self, f: impl Fn(i32) -> i32) -> i32 {
+        self.0.iter().map(|x| f(*x)).sum()
+    }
+}
+
+assert_eq!(AnyNumbers::from(List(vec![1, 2, 3])).map(|x| x * 2), 12);
+```
+
+`impl Trait` can be nested in other types, e.g. `&[impl Trait]` or `Vec<impl Trait>` for batches of values of one type,
+mixed types can be passed as `&[Box<dyn Trait>]`
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Scale {
+        fn batch(&self, items: &[impl Into<u32> + Copy]) -> u32;
+    }
+
+    pub enum AnyScale {
+        Total(Total),
+    }
+);
+
+pub struct Total(u32);
+
+impl Scale for Total {
+    fn batch(&self, items: &[impl Into<u32> + Copy]) -> u32 {
+        self.0 + items.iter().map(|item| (*item).into()).sum::<u32>()
+    }
+}
+
+assert_eq!(AnyScale::from(Total(1)).batch(&[1u8, 2]), 4);
+```
+
+Named parameters are forwarded with turbofish also when the method takes `impl Trait` arguments,
+only the named ones are listed there, anonymous ones are left to inference (`v.render_into::<W>(indent, out)`)
+```
+use declarative_enum_dispatch::enum_dispatch;
+use std::fmt::Write;
+
+enum_dispatch!(
+    pub trait Render {
+        fn render_into<W: Write>(&self, indent: impl Into<usize>, out: &mut W);
+    }
+
+    pub enum AnyWidget {
+        Label(Label),
+    }
+);
+
+pub struct Label(&'static str);
+
+impl Render for Label {
+    fn render_into<W: Write>(&self, indent: impl Into<usize>, out: &mut W) {
+        write!(out, "{:indent$}{}", "", self.0, indent = indent.into()).unwrap();
+    }
+}
+
+let mut out = String::new();
+AnyWidget::from(Label("title")).render_into(2u8, &mut out);
+assert_eq!(out, "  title");
+```
+
+## Wrapped variant values
+Variant can hold a wrapper of the type implementing the trait, e.g. `Arc<Type>`.
+`#[dispatch(via = accessor)]` on the variant sets a closure or function reaching the implementing value,
+it receives the variant value the same way as the receiver of the method (`&Arc<Type>` for `&self`),
+so traits mixing `&self` and `&mut self` methods need a wrapper that works for both
+```
+use std::sync::Arc;
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+        #[dispatch(via = |shared| &**shared)]
+        Shared(Arc<Square>),
+        #[dispatch(no_from)]
+        #[dispatch(via = Box::as_ref)]
+        Boxed(Box<Square>),
+    }
+);
+
+pub struct Square(f32);
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+assert_eq!(AnyShape::from(Arc::new(Square(2.0))).area(), 4.0);
+assert_eq!(AnyShape::Boxed(Box::new(Square(3.0))).area(), 9.0);
+```
+
+`#[dispatch(from = Type)]` adds `From<Type>` converting the value into the variant type with `From`,
+so a large value can be boxed on conversion. It's generated along with the conversion of the variant type unless `no_from` is set
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+        #[dispatch(from = Mesh)]
+        #[dispatch(via = Box::as_ref)]
+        Mesh(Box<Mesh>),
+    }
+);
+
+pub struct Square(f32);
+pub struct Mesh([f32; 64]);
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+impl Area for Mesh {
+    fn area(&self) -> f32 {
+        self.0.iter().sum()
+    }
+}
+
+let mesh = AnyShape::from(Mesh([0.5; 64]));
+assert!(matches!(mesh, AnyShape::Mesh(_)));
+assert_eq!(mesh.area(), 32.0);
+assert_eq!(AnyShape::from(Box::new(Mesh([1.0; 64]))).area(), 64.0);
+assert!(std::mem::size_of::<AnyShape>() < std::mem::size_of::<Mesh>());
+```
+
+## Explicit discriminants
+Variants can have explicit discriminants, they are written after the predicate name if there is one.
+Discriminants of variants with fields require primitive `#[repr(...)]` on the enum
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Instruction {
+        fn run(&self, a: i32, b: i32) -> i32;
+    }
+
+    pub enum Flag {
+        Carry = 1,
+        Zero = 2,
+    }
+);
+
+enum_dispatch!(
+    pub trait Execute {
+        fn execute(&self, a: i32, b: i32) -> i32;
+    }
+
+    #[repr(u8)]
+    pub enum OpCode {
+        Add(Add) = 0x10,
+        Sub(Sub) as is_sub = 0x20,
+    }
+);
+
+pub struct Carry;
+pub struct Zero;
+pub struct Add;
+pub struct Sub;
+
+impl Instruction for Carry {
+    fn run(&self, a: i32, b: i32) -> i32 {
+        a.overflowing_add(b).1 as i32
+    }
+}
+
+impl Instruction for Zero {
+    fn run(&self, a: i32, b: i32) -> i32 {
+        (a + b == 0) as i32
+    }
+}
+
+impl Execute for Add {
+    fn execute(&self, a: i32, b: i32) -> i32 {
+        a + b
+    }
+}
+
+impl Execute for Sub {
+    fn execute(&self, a: i32, b: i32) -> i32 {
+        a - b
+    }
+}
+
+assert_eq!(Flag::Zero as u8, 2);
+assert_eq!(Flag::Zero.run(1, -1), 1);
+assert!(OpCode::from(Sub).is_sub());
+assert_eq!(OpCode::from(Add).execute(1, 2), 3);
+```
+
+`#[repr(...)]` of the enum is kept as is, so enums of unit variants keep C-compatible layout and can be passed over FFI
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Level {
+        fn name(&self) -> &'static str;
+    }
+
+    #[repr(u8)]
+    pub enum LogLevel {
+        Info = 1,
+        Warn,
+    }
+);
+
+pub struct Info;
+pub struct Warn;
+
+impl Level for Info {
+    fn name(&self) -> &'static str {
+        "info"
+    }
+}
+
+impl Level for Warn {
+    fn name(&self) -> &'static str {
+        "warn"
+    }
+}
+
+assert_eq!(std::mem::size_of::<LogLevel>(), 1);
+assert_eq!(LogLevel::Warn as u8, 2);
+```
+
+## Documentation of methods
+Doc comments of any content are kept on the trait methods, including code blocks, hidden lines and tables
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    /// Storage of bytes
+    ///
+    /// | method | result |
+    /// |--------|--------|
+    /// | `len`  | bytes  |
+    pub trait Storage {
+        /// Number of stored bytes
+        ///
+        /// ```
+        /// # fn hidden() {}
+        /// let len = 1 | 2;
+        /// assert_eq!(len, 3);
+        /// ```
+        fn len(&self) -> usize;
+        #[doc = "Whether storage is empty, `{`, `}` and `;` are fine too"]
+        /** Block comments work as well
+            ```text
+            | a | b |
+            ```
+        */
+        fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+        /// Generic method with docs
+        /// ```ignore
+        /// storage.get::<u8>();
+        /// ```
+        fn get<T: From<u8>>(&self) -> T where T: Copy;
+    }
+
+    pub enum AnyStorage {
+        Memory(Memory),
+    }
+);
+
+pub struct Memory(Vec<u8>);
+
+impl Storage for Memory {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+    fn get<T: From<u8>>(&self) -> T where T: Copy {
+        T::from(self.0[0])
+    }
+}
+
+let storage = AnyStorage::from(Memory(vec![7]));
+assert_eq!(storage.len(), 1);
+assert!(!storage.is_empty());
+assert_eq!(storage.get::<u32>(), 7);
+```
+
+## Supertraits
+Supertraits, including generic ones, are kept on the trait declaration.
+Supertraits aren't implemented by the macro, so the enum needs its own implementations of them (derived or written by hand).
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+pub trait Format<F> {
+    fn format(&self) -> F;
+}
+
+pub struct Json(String);
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Json(value)
+    }
+}
+
+enum_dispatch!(
+    pub trait Encode<F>: Format<F> + Clone where F: From<String> {
+        fn encode(&self) -> F;
+    }
+
+    #[derive(Clone)]
+    pub enum AnyMessage {
+        Ping(Ping),
+    }
+);
+
+#[derive(Clone)]
+pub struct Ping;
+
+impl<F: From<String>> Format<F> for Ping {
+    fn format(&self) -> F {
+        F::from("ping".to_string())
+    }
+}
+
+impl<F: From<String>> Format<F> for AnyMessage {
+    fn format(&self) -> F {
+        F::from("message".to_string())
+    }
+}
+
+impl<F: From<String>> Encode<F> for Ping {
+    fn encode(&self) -> F {
+        self.format()
+    }
+}
+
+let message = AnyMessage::from(Ping);
+assert_eq!(Encode::<Json>::encode(&message).0, "ping");
+assert_eq!(Format::<Json>::format(&message).0, "message");
+```
+
+## Conditional methods
+`#[cfg(...)]` on a trait method is kept on the whole generated method, so with every kind of receiver
+the method is left out of the impl together with the trait one
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Counter {
+        #[cfg(any())]
+        fn get(&self) -> u32;
+        #[cfg(any())]
+        fn bump(&mut self);
+        #[cfg(any())]
+        fn into_count(self) -> u32;
+
+        #[cfg(all())]
+        fn peek(&self) -> u32;
+        #[cfg(all())]
+        fn reset(&mut self);
+        #[cfg(all())]
+        fn finish(self) -> u32;
+    }
+
+    pub enum AnyCounter {
+        Simple(Simple),
+    }
+);
+
+pub struct Simple(u32);
+
+impl Counter for Simple {
+    fn peek(&self) -> u32 {
+        self.0
+    }
+
+    fn reset(&mut self) {
+        self.0 = 0;
+    }
+
+    fn finish(self) -> u32 {
+        self.0
+    }
+}
+
+let mut counter = AnyCounter::from(Simple(3));
+assert_eq!(counter.peek(), 3);
+counter.reset();
+assert_eq!(counter.finish(), 0);
+```
+
+Disabled method can't be called on the enum
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Counter {
+        #[cfg(any())]
+        fn bump(&mut self);
+    }
+
+    pub enum AnyCounter {
+        Simple(Simple),
+    }
+);
+
+pub struct Simple(u32);
+
+impl Counter for Simple {}
+
+let mut counter = AnyCounter::from(Simple(3));
+// error: no method named `bump` found
+counter.bump();
+```
+
+## Conditional variants
+Every item generated for a variant disabled with `#[cfg(...)]` is disabled together with it:
+match arms, `From` and `TryFrom` impls, variant predicates, visitor methods and trait object conversions,
+so the variant type may not exist at all in that configuration and the matches stay exhaustive
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+        #[cfg(feature = "platform_specific")]
+        Cube(Cube),
+    }
+);
+
+pub struct Square(f32);
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+// `Cube` is never declared, the feature is off
+assert_eq!(AnyShape::from(Square(2.0)).area(), 4.0);
+```
+
+## async-trait
+`#[async_trait]` rewrites the items it is applied to, but methods generated by the macro are still macro calls at that point,
+so it can't be used on a trait declared inside of the macro. `#[dispatch(async_trait)]` on the trait does the same rewrite instead:
+every `async fn` of the trait and of the generated impl returns `Pin<Box<dyn Future + Send>>` like `#[async_trait]` expands it,
+so the variants implement the trait with `#[async_trait]` and the enum stays usable as `dyn Trait`.
+Futures of the variants are returned as is, default implementations are boxed the same way.
+`#[dispatch(async_trait(?Send))]` matches `#[async_trait(?Send)]`, see `examples/async_trait.rs`
+```
+use async_trait::async_trait;
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    #[dispatch(async_trait)]
+    pub trait Job: Sync {
+        async fn run(&self, n: u32) -> u32;
+    }
+
+    pub enum AnyJob {
+        Double(Double),
+    }
+);
+
+pub struct Double;
+
+#[async_trait]
+impl Job for Double {
+    async fn run(&self, n: u32) -> u32 {
+        n * 2
+    }
+}
+
+let job: Box<dyn Job> = Box::new(AnyJob::from(Double));
+let _future = job.run(2);
+```
+Trait declared with `#[async_trait]` outside of the macro is dispatched with `use trait` and the same option, its methods are listed as `async fn`.
+Generic methods, receivers other than `self`, `&self` and `&mut self` and elided lifetimes inside of argument types, e.g. `Option<&str>`,
+aren't supported by the rewrite, such methods can be listed as `#[async_trait]` expands them without the option
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    #[dispatch(async_trait)]
+    pub trait Job {
+        // error: generic method `async fn run` is not supported with `#[dispatch(async_trait)]`
+        async fn run<T: Send>(&self, input: T);
+    }
+
+    pub enum AnyJob {
+        Double(Double),
+    }
+);
+
+pub struct Double;
+```
+
+## Extra methods
+`extra impl { ... }` after the enum is emitted as an inherent impl of the enum with its generics,
+so hand-written helpers can be kept together with the dispatch
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> f32;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+        Circle(Circle),
+    }
+
+    extra impl {
+        pub fn is_large(&self) -> bool {
+            self.area() > 10.0
+        }
+
+        pub fn total(shapes: &[Self]) -> f32 {
+            shapes.iter().map(Area::area).sum()
+        }
+    }
+);
+
+pub struct Square(f32);
+pub struct Circle(f32);
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+}
+
+impl Area for Circle {
+    fn area(&self) -> f32 {
+        3.0 * self.0 * self.0
+    }
+}
+
+let shapes = [AnyShape::from(Square(2.0)), AnyShape::from(Circle(2.0))];
+assert!(!shapes[0].is_large());
+assert!(shapes[1].is_large());
+assert_eq!(AnyShape::total(&shapes), 16.0);
+```
+
+## Borrowed return values
+Returned references keep lifetime elided from `&self`, so they borrow the enum like they borrow the variant value
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Named {
+        fn label(&self) -> &str;
+    }
+
+    pub enum AnyNamed {
+        File(File),
+    }
+);
+
+pub struct File(String);
+
+impl Named for File {
+    fn label(&self) -> &str {
+        &self.0
+    }
+}
+
+let named = AnyNamed::from(File("log".to_string()));
+assert_eq!(named.label(), "log");
+```
+
+Mutable references returned from `&mut self` methods borrow the enum mutably
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Buffer {
+        fn buffer_mut(&mut self) -> &mut Vec<u8>;
+    }
+
+    pub enum AnyBuffer {
+        Memory(Memory),
+    }
+);
+
+pub struct Memory(Vec<u8>);
+
+impl Buffer for Memory {
+    fn buffer_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.0
+    }
+}
+
+let mut buffer = AnyBuffer::from(Memory(vec![1]));
+buffer.buffer_mut().push(2);
+assert_eq!(buffer.buffer_mut(), &[1, 2]);
+```
+
+## Skipped variants
+`#[dispatch(skip(Variant, ...))]` on a method with default implementation runs it for the listed variants instead of dispatching,
+`self` in the default body is the enum. `#[dispatch(skip)]` without the list runs it for all variants
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Shape {
+        fn area(&self) -> f32;
+        #[dispatch(skip(Circle, Dot))]
+        fn describe(&self) -> String {
+            format!("shape of area {}", self.area())
+        }
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+        Circle(Circle),
+        Dot,
+    }
+);
+
+pub struct Square(f32);
+pub struct Circle(f32);
+pub struct Dot;
+
+impl Shape for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+    fn describe(&self) -> String {
+        format!("square {}", self.0)
+    }
+}
+impl Shape for Circle {
+    fn area(&self) -> f32 {
+        3.0 * self.0 * self.0
+    }
+    fn describe(&self) -> String {
+        "circle".to_string()
+    }
+}
+impl Shape for Dot {
+    fn area(&self) -> f32 {
+        0.0
+    }
+}
+
+assert_eq!(AnyShape::from(Square(2.0)).describe(), "square 2");
+assert_eq!(AnyShape::from(Circle(1.0)).describe(), "shape of area 3");
+assert_eq!(AnyShape::Dot.describe(), "shape of area 0");
+```
+
+Method without default implementation can't skip variants
+```compile_fail
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Shape {
+        // error: `fn area` has no default implementation to run for skipped variants
+        #[dispatch(skip(Dot))]
+        fn area(&self) -> f32;
+    }
+
+    pub enum AnyShape {
+        Dot,
+    }
+);
+
+pub struct Dot;
+
+impl Shape for Dot {
+    fn area(&self) -> f32 {
+        0.0
+    }
+}
+```
+
+## Hand-written methods
+Trait impl can't be split into several blocks, so `#[dispatch(manual)]` on a method makes the generated method
+call the inherent method of the enum with the same name and signature, written by hand, e.g. to compute the result without dispatching.
+Inherent method is found first by method calls too. Without it the generated method calls itself, which is reported by `unconditional_recursion` lint
+```
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Area {
+        #[dispatch(manual)]
+        fn area(&self) -> f32;
+        fn side(&self) -> f32;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+        Dot,
+    }
+);
+
+pub struct Square(f32);
+pub struct Dot;
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+    fn side(&self) -> f32 {
+        self.0
+    }
+}
+
+impl Area for Dot {
+    fn area(&self) -> f32 {
+        0.0
+    }
+    fn side(&self) -> f32 {
+        0.0
+    }
+}
+
+impl AnyShape {
+    pub fn area(&self) -> f32 {
+        self.side() * self.side()
+    }
+}
+
+fn total(shapes: &[impl Area]) -> f32 {
+    shapes.iter().map(Area::area).sum()
+}
+
+assert_eq!(AnyShape::from(Square(2.0)).area(), 4.0);
+assert_eq!(total(&[AnyShape::from(Square(2.0)), AnyShape::Dot]), 4.0);
+```
+
+## Visibility
+Trait and enum keep their own visibility, generated impls have none and accessors of the enum use visibility of the enum,
+so any combination of them compiles without warnings
+
+## Proc-macro
+Declarative macro needs the trait and the enum in one call and parses them by tokens, which limits the syntax it accepts.
+The `proc-macro` feature adds `#[enum_dispatch]` attribute macro in `attributes` instead, parsed with `syn`.
+On a trait it registers the trait for dispatch, on an enum it implements `From` for every variant and the listed traits.
+Generic parameters of the trait and of the enum are generic parameters of the impl, ones with the same name are shared,
+associated types are taken from the first variant.
+The trait has to come from the same crate, and types in its signatures have to be nameable where the enum is
+```ignore
+use declarative_enum_dispatch::attributes::enum_dispatch;
+
+#[enum_dispatch]
+pub trait Container<T> {
+    fn get(&self, index: usize) -> Option<&T>;
+}
+
+#[enum_dispatch(Container)]
+pub enum AnyContainer<T> {
+    List(Vec<T>),
+    One(One<T>),
+}
+
+pub struct One<T>(T);
+
+impl<T> Container<T> for Vec<T> {
+    fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+}
+
+impl<T> Container<T> for One<T> {
+    fn get(&self, index: usize) -> Option<&T> {
+        (index == 0).then_some(&self.0)
+    }
+}
+
+assert_eq!(AnyContainer::from(vec![1, 2]).get(1), Some(&2));
+```
+*/
+
+/// Name of the current variant, implemented along with inherent `variant_name()` for enums with `#[dispatch(generate(variant_names))]`.
+///
+/// Default methods of the trait can require it with `where Self: VariantName` to use the name of the variant,
+/// see [Variant names](crate#variant-names)
+pub trait VariantName {
+    /// Name of the current variant
+    fn variant_name(&self) -> &'static str;
+}
+
+#[doc(hidden)]
+// applies accessor of `#[dispatch(via = accessor)]`, so types of closure parameters are inferred from the variant value
+#[inline]
+pub fn __via<T, R>(value: T, accessor: impl FnOnce(T) -> R) -> R {
+    accessor(value)
+}
+
+#[macro_export]
+#[doc(hidden)]
+// builds a single method of the trait
+// `$ctx` is `[trait]` to re-emit method declaration or `[impl [variants] enum_name]` to generate dispatch
+macro_rules! __build_method {
+    // markers like `#[dispatch(box)]` are not real attributes, so they are turned into flags
+    (@attrs $ctx:tt; [#[dispatch(box)] $($attrs:tt)*]; $kept:tt; [$($flags:tt)*]; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs $ctx; [$($attrs)*]; $kept; [$($flags)* box]; $($rest)*);
+    };
+    // `#[dispatch(manual)]` forwards the method to the inherent method of the enum with the same name written by hand
+    (@attrs $ctx:tt; [#[dispatch(manual)] $($attrs:tt)*]; $kept:tt; [$($flags:tt)*]; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs $ctx; [$($attrs)*]; $kept; [manual $($flags)*]; $($rest)*);
+    };
+    // `#[dispatch(skip(Variant, ...))]` runs default implementation of the method for listed variants, `#[dispatch(skip)]` for all of them,
+    // it is kept first, so the body is added to it once the method is parsed
+    (@attrs $ctx:tt; [#[dispatch(skip($($skipped:ident),+ $(,)?))] $($attrs:tt)*]; $kept:tt; [$($flags:tt)*]; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs $ctx; [$($attrs)*]; $kept; [skip [$($skipped)+] $($flags)*]; $($rest)*);
+    };
+    (@attrs $ctx:tt; [#[dispatch(skip)] $($attrs:tt)*]; $kept:tt; [$($flags:tt)*]; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs $ctx; [$($attrs)*]; $kept; [skip [] $($flags)*]; $($rest)*);
+    };
+    // `#[dispatch(attr(...))]` is applied only to the generated method, e.g. `#[dispatch(attr(inline))]`
+    (@attrs [trait]; [#[dispatch(attr($($attr:tt)*))] $($attrs:tt)*]; $kept:tt; $flags:tt; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs [trait]; [$($attrs)*]; $kept; $flags; $($rest)*);
+    };
+    // documentation belongs to the trait, so it isn't repeated on the generated method
+    (@attrs [impl $($ctx:tt)*]; [#[doc $($doc:tt)*] $($attrs:tt)*]; $kept:tt; $flags:tt; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs [impl $($ctx)*]; [$($attrs)*]; $kept; $flags; $($rest)*);
+    };
+    // calls of deprecated method through the enum are reported by the trait method,
+    // generated method calls the deprecated method of the variant itself, so the warning is allowed there
+    (@attrs [impl $($ctx:tt)*]; [#[deprecated $($deprecated:tt)*] $($attrs:tt)*]; [$($kept:tt)*]; $flags:tt; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs [impl $($ctx)*]; [$($attrs)*]; [$($kept)* #[allow(deprecated)]]; $flags; $($rest)*);
+    };
+    // explicit `inline` attribute replaces the default `#[inline]`
+    (@attrs $ctx:tt; [#[dispatch(attr(inline $($inline:tt)*))] $($attrs:tt)*]; [#[inline] $($kept:tt)*]; $flags:tt; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs $ctx; [$($attrs)*]; [$($kept)* #[inline $($inline)*]]; $flags; $($rest)*);
+    };
+    (@attrs $ctx:tt; [#[inline $($inline:tt)*] $($attrs:tt)*]; [#[inline] $($kept:tt)*]; $flags:tt; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs $ctx; [$($attrs)*]; [$($kept)* #[inline $($inline)*]]; $flags; $($rest)*);
+    };
+    // conditional `inline` replaces the default `#[inline]` only where its condition holds
+    (@attrs $ctx:tt; [#[dispatch(attr(cfg_attr($condition:meta, inline $($inline:tt)*)))] $($attrs:tt)*]; [#[inline] $($kept:tt)*]; $flags:tt; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs $ctx; [$($attrs)*]; [$($kept)* #[cfg_attr($condition, inline $($inline)*)] #[cfg_attr(not($condition), inline)]]; $flags; $($rest)*);
+    };
+    (@attrs $ctx:tt; [#[cfg_attr($condition:meta, inline $($inline:tt)*)] $($attrs:tt)*]; [#[inline] $($kept:tt)*]; $flags:tt; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs $ctx; [$($attrs)*]; [$($kept)* #[cfg_attr($condition, inline $($inline)*)] #[cfg_attr(not($condition), inline)]]; $flags; $($rest)*);
+    };
+    (@attrs $ctx:tt; [#[dispatch(attr($($attr:tt)*))] $($attrs:tt)*]; [$($kept:tt)*]; $flags:tt; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs $ctx; [$($attrs)*]; [$($kept)* #[$($attr)*]]; $flags; $($rest)*);
+    };
+    (@attrs $ctx:tt; [#[dispatch($($option:tt)*)] $($attrs:tt)*]; $kept:tt; $flags:tt; $($rest:tt)*) => {
+        compile_error!(concat!("unknown dispatch option `", stringify!($($option)*), "`"));
+    };
+    (@attrs $ctx:tt; [#[$($attr:tt)*] $($attrs:tt)*]; [$($kept:tt)*]; $flags:tt; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs $ctx; [$($attrs)*]; [$($kept)* #[$($attr)*]]; $flags; $($rest)*);
+    };
+    (@attrs [trait]; []; [$($attr:tt)*]; $flags:tt; $($method_def:ident)+; { [$($generics:tt)*]; $turbofish:tt }; ($($params:tt)*); [$($ret:tt)*]; [$($where_clause:tt)*]; $body:tt) => {
+        $($attr)* $($method_def)+<$($generics)*>($($params)*) $($ret)* $($where_clause)* $body
+    };
+    (@attrs $ctx:tt; []; $attrs:tt; [skip $skipped:tt $($flags:tt)*]; $($method_def:ident)+; $generics:tt; $params:tt; $ret:tt; $where_clause:tt; ;) => {
+        compile_error!(concat!("`", stringify!($($method_def)+), "` has no default implementation to run for skipped variants"));
+    };
+    (@attrs $ctx:tt; []; $attrs:tt; [skip $skipped:tt $($flags:tt)*]; $($method_def:ident)+; $generics:tt; ($($params:tt)*); $ret:tt; $where_clause:tt; $body:tt) => {
+        $crate::__build_method!(@receiver { $ctx; $attrs; [{ $skipped $body } $($flags)*]; [$($method_def)+]; $generics; $ret; $where_clause }; ($($params)*));
+    };
+    (@attrs $ctx:tt; []; $attrs:tt; $flags:tt; $($method_def:ident)+; $generics:tt; ($($params:tt)*); $ret:tt; $where_clause:tt; $body:tt) => {
+        $crate::__build_method!(@receiver { $ctx; $attrs; $flags; [$($method_def)+]; $generics; $ret; $where_clause }; ($($params)*));
+    };
+
+    // parses receiver of the method
+    // there is a variant for every supported receiver because declarative macro can't handle self pattern
+    (@receiver $head:tt; ($self_:ident $(, $($args:tt)*)?)) => {
+        $crate::__build_method!(@args [@build $head; { $self_ }; { $self_ }; [{ } { }];]; []; []; { $($($args)*)? });
+    };
+    (@receiver $head:tt; (&$self_:ident $(, $($args:tt)*)?)) => {
+        $crate::__build_method!(@args [@build $head; { &$self_ }; { $self_ }; [{ } { & }];]; []; []; { $($($args)*)? });
+    };
+    (@receiver $head:tt; (&mut $self_:ident $(, $($args:tt)*)?)) => {
+        $crate::__build_method!(@args [@build $head; { &mut $self_ }; { $self_ }; [{ } { &mut }];]; []; []; { $($($args)*)? });
+    };
+    (@receiver $head:tt; (&$lifetime:lifetime $self_:ident $(, $($args:tt)*)?)) => {
+        $crate::__build_method!(@args [@build $head; { &$lifetime $self_ }; { $self_ }; [{ } { & }];]; []; []; { $($($args)*)? });
+    };
+    (@receiver $head:tt; (&$lifetime:lifetime mut $self_:ident $(, $($args:tt)*)?)) => {
+        $crate::__build_method!(@args [@build $head; { &$lifetime mut $self_ }; { $self_ }; [{ } { &mut }];]; []; []; { $($($args)*)? });
+    };
+    // `Box<Self>` can be moved out of, variant value is boxed again
+    (@receiver $head:tt; ($self_:ident: Box<Self> $(, $($args:tt)*)?)) => {
+        $crate::__build_method!(@args [@build $head; { $self_: Box<Self> }; { *$self_ }; [{ Box::new } { }];]; []; []; { $($($args)*)? });
+    };
+    // shared pointers (`Rc<Self>`, `Arc<Self>`) can't be moved out of, so enum has to be `Clone`
+    (@receiver $head:tt; ($self_:ident: Rc<Self> $(, $($args:tt)*)?)) => {
+        $crate::__build_method!(@args [@build $head; { $self_: Rc<Self> }; { ::std::rc::Rc::unwrap_or_clone($self_) }; [{ ::std::rc::Rc::new } { }];]; []; []; { $($($args)*)? });
+    };
+    (@receiver $head:tt; ($self_:ident: Arc<Self> $(, $($args:tt)*)?)) => {
+        $crate::__build_method!(@args [@build $head; { $self_: Arc<Self> }; { ::std::sync::Arc::unwrap_or_clone($self_) }; [{ ::std::sync::Arc::new } { }];]; []; []; { $($($args)*)? });
+    };
+    (@receiver $head:tt; ($self_:ident: &Rc<Self> $(, $($args:tt)*)?)) => {
+        $crate::__build_method!(@args [@build $head; { $self_: &Rc<Self> }; { Clone::clone(&**$self_) }; [{ &::std::rc::Rc::new } { }];]; []; []; { $($($args)*)? });
+    };
+    (@receiver $head:tt; ($self_:ident: &Arc<Self> $(, $($args:tt)*)?)) => {
+        $crate::__build_method!(@args [@build $head; { $self_: &Arc<Self> }; { Clone::clone(&**$self_) }; [{ &::std::sync::Arc::new } { }];]; []; []; { $($($args)*)? });
+    };
+    // other pointers can't be rebuilt around the variant value
+    (@receiver { $ctx:tt; $attrs:tt; $flags:tt; [$($method_def:ident)+]; $($rest:tt)* }; ($self_:ident: $ptr:ident<Self> $(, $($args:tt)*)?)) => {
+        compile_error!(concat!("receiver `", stringify!($ptr), "<Self>` of method `", stringify!($($method_def)+), "` is not supported, expected `Box<Self>`, `Rc<Self>` or `Arc<Self>`"));
+    };
+    (@receiver { $ctx:tt; $attrs:tt; $flags:tt; [$($method_def:ident)+]; $($rest:tt)* }; ($self_:ident: &$ptr:ident<Self> $(, $($args:tt)*)?)) => {
+        compile_error!(concat!("receiver `&", stringify!($ptr), "<Self>` of method `", stringify!($($method_def)+), "` is not supported, expected `&Rc<Self>` or `&Arc<Self>`"));
+    };
+    (@receiver { $ctx:tt; $attrs:tt; $flags:tt; [$($method_def:ident)+]; $($rest:tt)* }; (self: $($params:tt)*)) => {
+        compile_error!(concat!("unsupported receiver of method `", stringify!($($method_def)+), "`"));
+    };
+    // associated function doesn't have a value to dispatch on, so it is called on the type chosen for the enum
+    (@receiver { [impl [$($(#[$var_attr:meta])* $variant:ident $pattern:tt $binding:tt),+; { $fallback:tt [$associated_type:ty] $inline:tt }] $enum_name:ident $trait:tt]; $attrs:tt; $flags:tt; $method_def:tt; $generics:tt; $ret:tt; $where_clause:tt }; ($($args:tt)*)) => {
+        $crate::__build_method!(@args [@associated { [$associated_type] $enum_name $trait; $attrs; $flags; $method_def; $generics; $ret; $where_clause };]; []; []; { $($args)* });
+    };
+    (@receiver { $ctx:tt; $attrs:tt; $flags:tt; [$($method_def:ident)+]; $($rest:tt)* }; ($($params:tt)*)) => {
+        compile_error!(concat!("method `", stringify!($($method_def)+), "` should receive self, associated functions are dispatched to the type from `#[dispatch(associated = Type)]` on the enum"));
+    };
+
+    // parses arguments after the receiver into `[signature]` and `[call arguments]`, then continues with `$next`
+    // `mut` is needed only by the default implementation, generated method just forwards the value
+    (@args [$($next:tt)*]; $sig:tt; [$($call:tt)*]; { }) => {
+        $crate::__build_method!($($next)* $sig; ($($call)*));
+    };
+    (@args $next:tt; $sig:tt; $call:tt; { mut $arg:ident: $($rest:tt)* }) => {
+        $crate::__build_method!(@args $next; $sig; $call; { $arg: $($rest)* });
+    };
+    (@args $next:tt; [$($sig:tt)*]; [$($call:tt)*]; { $arg:ident: $arg_ty:ty $(, $($rest:tt)*)? }) => {
+        $crate::__build_method!(@args $next; [$($sig)* $arg: $arg_ty,]; [$($call)* $arg,]; { $($($rest)*)? });
+    };
+    // other patterns, e.g. `(x, y): (i32, i32)` or `_: Event`, can't be forwarded, so argument is renamed
+    // `arg` is hygienic and comes from a separate expansion for every argument, so names don't clash
+    (@args $next:tt; $sig:tt; $call:tt; { $($rest:tt)+ }) => {
+        $crate::__build_method!(@pattern $next; $sig; $call; { $($rest)+ });
+    };
+    (@pattern $next:tt; [$($sig:tt)*]; [$($call:tt)*]; { : $arg_ty:ty $(, $($rest:tt)*)? }) => {
+        $crate::__build_method!(@args $next; [$($sig)* arg: $arg_ty,]; [$($call)* arg,]; { $($($rest)*)? });
+    };
+    // anonymous parameters like `fn notify(&self, Event)` are accepted only by 2015 edition
+    (@pattern $next:tt; $sig:tt; $call:tt; { $(, $($rest:tt)*)? }) => {
+        compile_error!("method arguments should be named, use `_: Type` for unused ones");
+    };
+    (@pattern $next:tt; $sig:tt; $call:tt; { $pattern:tt $($rest:tt)* }) => {
+        $crate::__build_method!(@pattern $next; $sig; $call; { $($rest)* });
+    };
+
+    // signature is written by the user, so its lints are allowed on generated methods and reported on the trait only
+    // inherent method takes the receiver as is, so it is passed instead of the scrutinee
+    (@build { [impl $variants:tt $enum_name:ident $trait:tt]; [$($attr:tt)*]; [manual $($flags:tt)*]; [$($method_def:ident)+]; { [$($generics:tt)*]; $turbofish:tt }; [$($ret:tt)*]; [$($where_clause:tt)*] }; {$($receiver:tt)*}; $scrutinee:tt; $wrap:tt; [$($sig:tt)*]; $call:tt) => {
+        #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+        $($attr)* $($method_def)+<$($generics)*>($($receiver)*, $($sig)*) $($ret)* $($where_clause)* {
+            $crate::__build_method!(@make_match { $crate::__build_method!(@self_of $($receiver)*) }; $wrap; [$($method_def)+]; $turbofish; [manual]; $enum_name $trait; $call; [$($flags)*]; [$($ret)*]; [])
+        }
+    };
+    (@build { [impl $variants:tt $enum_name:ident $trait:tt]; [$($attr:tt)*]; $flags:tt; [$($method_def:ident)+]; { [$($generics:tt)*]; $turbofish:tt }; [$($ret:tt)*]; [$($where_clause:tt)*] }; {$($receiver:tt)*}; $scrutinee:tt; $wrap:tt; [$($sig:tt)*]; $call:tt) => {
+        #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+        $($attr)* $($method_def)+<$($generics)*>($($receiver)*, $($sig)*) $($ret)* $($where_clause)* {
+            $crate::__build_method!(@make_match $scrutinee; $wrap; [$($method_def)+]; $turbofish; $variants; $enum_name $trait; $call; $flags; [$($ret)*]; [])
+        }
+    };
+    (@associated { [$associated_type:ty] $enum_name:ident $trait:tt; [$($attr:tt)*]; [manual $($flags:tt)*]; [$($method_def:ident)+]; { [$($generics:tt)*]; $turbofish:tt }; [$($ret:tt)*]; [$($where_clause:tt)*] }; [$($sig:tt)*]; $call:tt) => {
+        #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+        $($attr)* $($method_def)+<$($generics)*>($($sig)*) $($ret)* $($where_clause)* {
+            $crate::__build_method!(@make_match {}; [{} {}]; [$($method_def)+]; $turbofish; [manual]; $enum_name $trait; $call; [$($flags)*]; [$($ret)*]; [])
+        }
+    };
+    (@associated { [$associated_type:ty] $enum_name:ident $trait:tt; [$($attr:tt)*]; $flags:tt; [$($method_def:ident)+]; { [$($generics:tt)*]; $turbofish:tt }; [$($ret:tt)*]; [$($where_clause:tt)*] }; [$($sig:tt)*]; $call:tt) => {
+        #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+        $($attr)* $($method_def)+<$($generics)*>($($sig)*) $($ret)* $($where_clause)* {
+            $crate::__build_method!(@make_match {}; [{} {}]; [$($method_def)+]; $turbofish; [associated $associated_type]; $enum_name $trait; $call; $flags; [$($ret)*]; [])
+        }
+    };
+
+    // skipped variants are matched before the dispatch, so default body sees `self` as the enum
+    // without the list of variants nothing is dispatched, so variant types don't have to satisfy bounds of the method
+    (@make_match $scrutinee:tt; $wrap:tt; $method_def:tt; $turbofish:tt; $variants:tt; $enum_name:ident $trait:tt; $args:tt; [{ [] $body:tt } $($flags:tt)*]; $ret:tt; $post:tt) => {
+        $body
+    };
+    (@make_match {$($scrutinee:tt)*}; $wrap:tt; $method_def:tt; $turbofish:tt; $variants:tt; $enum_name:ident $trait:tt; $args:tt; [{ [$($skipped:ident)+] $body:tt } $($flags:tt)*]; $ret:tt; $post:tt) => {
+        match $($scrutinee)* {
+            $($enum_name::$skipped { .. } => $body,)+
+            #[allow(unreachable_patterns)]
+            _ => $crate::__build_method!(@make_match {$($scrutinee)*}; $wrap; $method_def; $turbofish; $variants; $enum_name $trait; $args; [$($flags)*]; $ret; $post),
+        }
+    };
+    // `post` is a list of transformations applied to the result of the call
+    (@make_match $scrutinee:tt; $wrap:tt; [async $($method_def:ident)+]; $turbofish:tt; $variants:tt; $enum_name:ident $trait:tt; $args:tt; $flags:tt; $ret:tt; [$($post:tt)*]) => {
+        $crate::__build_method!(@make_match $scrutinee; $wrap; [$($method_def)+]; $turbofish; $variants; $enum_name $trait; $args; $flags; $ret; [$($post)* await])
+    };
+    // `const` is kept in the signature only, call is the same
+    (@make_match $scrutinee:tt; $wrap:tt; [const $($method_def:ident)+]; $($rest:tt)*) => {
+        $crate::__build_method!(@make_match $scrutinee; $wrap; [$($method_def)+]; $($rest)*)
+    };
+    // call of unsafe method is wrapped into `unsafe` block
+    (@make_match $scrutinee:tt; $wrap:tt; [unsafe $($method_def:ident)+]; $turbofish:tt; $variants:tt; $enum_name:ident $trait:tt; $args:tt; $flags:tt; $ret:tt; [$($post:tt)*]) => {
+        $crate::__build_method!(@make_match $scrutinee; $wrap; [$($method_def)+]; $turbofish; $variants; $enum_name $trait; $args; $flags; $ret; [$($post)* unsafe])
+    };
+    (@make_match $scrutinee:tt; $wrap:tt; $method_def:tt; $turbofish:tt; $variants:tt; $enum_name:ident $trait:tt; $args:tt; [box $($flags:tt)*]; [-> impl $($bounds:tt)*]; $post:tt) => {
+        $crate::__build_method!(@dyn_bounds [$($bounds)*]; [$($bounds)*]; $scrutinee; $wrap; $method_def; $turbofish; $variants; $enum_name $trait; $args; [$($flags)*]; [-> impl $($bounds)*]; $post)
+    };
+    (@make_match $scrutinee:tt; $wrap:tt; [fn $method:ident]; $turbofish:tt; $variants:tt; $enum_name:ident $trait:tt; $args:tt; [box $($flags:tt)*]; $ret:tt; $post:tt) => {
+        compile_error!(concat!("method `", stringify!($method), "` should return `impl Trait` to be boxed"))
+    };
+    // `wrap` is applied to the variant value before the call, e.g. `Box::new` for `self: Box<Self>` receivers
+    // variant pattern binds the field implementing the trait to `binding`, unit variants use `[UnitStruct]` instead
+    // `fallback` is a wildcard arm, empty by default
+    (@make_match $scrutinee:tt; $wrap:tt; [fn $method:ident]; []; [associated $associated_type:ty]; $enum_name:ident [$train_name:ident [$($trait_args:tt)*] $call:tt]; $args:tt; []; $ret:tt; $post:tt) => {
+        $crate::__build_method!(@associated_returns $ret; { $crate::__build_method!(@post { <$associated_type as $train_name<$($trait_args)*>>::$method $args }; $post) })
+    };
+    (@make_match $scrutinee:tt; $wrap:tt; [fn $method:ident]; [$($turbofish:tt)+]; [associated $associated_type:ty]; $enum_name:ident [$train_name:ident [$($trait_args:tt)*] $call:tt]; $args:tt; []; $ret:tt; $post:tt) => {
+        $crate::__build_method!(@associated_returns $ret; { $crate::__build_method!(@post { <$associated_type as $train_name<$($trait_args)*>>::$method::<$($turbofish)+> $args }; $post) })
+    };
+    // inherent method is found before the trait one, it's called with the receiver as the first argument
+    (@make_match {}; $wrap:tt; [fn $method:ident]; [$($turbofish:tt)*]; [manual]; $enum_name:ident $trait:tt; ($($arg:tt)*); []; $ret:tt; $post:tt) => {
+        $crate::__build_method!(@post { Self::$method::<$($turbofish)*>($($arg)*) }; $post)
+    };
+    (@make_match {$($receiver:tt)+}; $wrap:tt; [fn $method:ident]; [$($turbofish:tt)*]; [manual]; $enum_name:ident $trait:tt; ($($arg:tt)*); []; $ret:tt; $post:tt) => {
+        $crate::__build_method!(@post { Self::$method::<$($turbofish)*>($($receiver)+, $($arg)*) }; $post)
+    };
+    (@self_of & $($lifetime:lifetime)? mut $self_:ident) => {
+        $self_
+    };
+    (@self_of & $($lifetime:lifetime)? $self_:ident) => {
+        $self_
+    };
+    (@self_of $self_:ident $(: $($ty:tt)*)?) => {
+        $self_
+    };
+    // `Self` of the associated type is converted into the enum with `From` generated for its variant
+    (@associated_returns [-> Self]; { $($expr:tt)* }) => {
+        From::from($($expr)*)
+    };
+    (@associated_returns [-> Option<Self>]; { $($expr:tt)* }) => {
+        Option::map($($expr)*, From::from)
+    };
+    (@associated_returns [-> Result<Self $($error:tt)*]; { $($expr:tt)* }) => {
+        Result::map($($expr)*, From::from)
+    };
+    (@associated_returns [-> Box<Self>]; { $($expr:tt)* }) => {
+        Box::new(From::from(*$($expr)*))
+    };
+    (@associated_returns $ret:tt; { $($expr:tt)* }) => {
+        $($expr)*
+    };
+    (@make_match {$($scrutinee:tt)*}; $wrap:tt; [fn $method:ident]; $turbofish:tt; [$($(#[$var_attr:meta])* $variant:ident $pattern:tt $binding:tt),+; { [$($fallback:tt)*] $associated:tt $inline:tt }]; $enum_name:ident $trait:tt; $args:tt; []; $ret:tt; $post:tt) => {
+        match $($scrutinee)* {
+            $(
+                $(#[$var_attr])*
+                $enum_name::$variant $pattern => $crate::__build_method!(@returns $ret; $enum_name::$variant $pattern; { $crate::__build_method!(@call $wrap; $binding; $trait; $method; $turbofish; $args; $post) }),
+            )+
+            $($fallback)*
+        }
+    };
+
+    // `Self` returned by the variant value is wrapped back into the same variant
+    (@returns [-> Self]; $enum_name:ident::$variant:ident ($value:ident $(,)?); { $($expr:tt)* }) => {
+        $enum_name::$variant($($expr)*)
+    };
+    (@returns [-> Self]; $enum_name:ident::$variant:ident {}; { $($expr:tt)* }) => {
+        { let _ = $($expr)*; $enum_name::$variant }
+    };
+    (@returns [-> Self]; $enum_name:ident::$variant:ident $pattern:tt; $expr:tt) => {
+        compile_error!(concat!("variant `", stringify!($variant), "` has several fields, so it can't be rebuilt from `Self` returned by the method"))
+    };
+    // `Self` inside of `Option`, `Result` and `Box` is rebuilt the same way, other wrappers are returned as is
+    (@returns [-> Option<Self>]; $enum_name:ident::$variant:ident $pattern:tt; { $($expr:tt)* }) => {
+        Option::map($($expr)*, |value| $crate::__build_method!(@returns [-> Self]; $enum_name::$variant $pattern; { value }))
+    };
+    (@returns [-> Result<Self $($error:tt)*]; $enum_name:ident::$variant:ident $pattern:tt; { $($expr:tt)* }) => {
+        Result::map($($expr)*, |value| $crate::__build_method!(@returns [-> Self]; $enum_name::$variant $pattern; { value }))
+    };
+    (@returns [-> Box<Self>]; $enum_name:ident::$variant:ident $pattern:tt; { $($expr:tt)* }) => {
+        Box::new($crate::__build_method!(@returns [-> Self]; $enum_name::$variant $pattern; { *$($expr)* }))
+    };
+    (@returns $ret:tt; $enum_name:ident::$variant:ident $pattern:tt; { $($expr:tt)* }) => {
+        $($expr)*
+    };
+
+    // boxed `impl Trait` borrows from arguments the same way as original, unless lifetime is specified explicitly
+    (@dyn_bounds [+ $lifetime:lifetime $($rest:tt)*]; [$($bounds:tt)*]; $($state:tt)*) => {
+        $crate::__build_method!(@boxed [$($bounds)*]; $($state)*)
+    };
+    (@dyn_bounds [$next:tt $($rest:tt)*]; $bounds:tt; $($state:tt)*) => {
+        $crate::__build_method!(@dyn_bounds [$($rest)*]; $bounds; $($state)*)
+    };
+    (@dyn_bounds []; [$($bounds:tt)*]; $($state:tt)*) => {
+        $crate::__build_method!(@boxed [$($bounds)* + '_]; $($state)*)
+    };
+    (@boxed $bounds:tt; $scrutinee:tt; $wrap:tt; $method_def:tt; $turbofish:tt; $variants:tt; $enum_name:ident $trait:tt; $args:tt; $flags:tt; $ret:tt; [$($post:tt)*]) => {
+        $crate::__build_method!(@make_match $scrutinee; $wrap; $method_def; $turbofish; $variants; $enum_name $trait; $args; $flags; $ret; [$($post)* box $bounds])
+    };
+
+    // method of a single trait is called with method syntax, so the variant value is dereferenced up to the type implementing the trait,
+    // otherwise it's called with fully qualified path, so it doesn't clash with inherent methods and other traits
+    // unit struct is borrowed the same way as `self`
+    (@call [$wrap:tt { $($unit_ref:tt)* }]; [$unit:ident]; $($rest:tt)*) => {
+        $crate::__build_method!(@call [$wrap {}]; ($($unit_ref)* $unit); $($rest)*)
+    };
+    (@call [{$($wrap:tt)*} $unit_ref:tt]; $value:tt; [$train_name:ident $trait_args:tt [method]]; $method:ident; [$($turbofish:tt)*]; ($($arg:tt)*); $post:tt) => {
+        $crate::__build_method!(@post { ($($wrap)*($value)).$method::<$($turbofish)*>($($arg)*) }; $post)
+    };
+    (@call [{$($wrap:tt)*} $unit_ref:tt]; $value:tt; [$train_name:ident [$($trait_args:tt)*] [qualified]]; $method:ident; []; ($($arg:tt)*); $post:tt) => {
+        $crate::__build_method!(@post { $train_name::<$($trait_args)*>::$method($($wrap)*($value), $($arg)*) }; $post)
+    };
+    (@call [{$($wrap:tt)*} $unit_ref:tt]; $value:tt; [$train_name:ident [$($trait_args:tt)*] [qualified]]; $method:ident; [$($turbofish:tt)+]; ($($arg:tt)*); $post:tt) => {
+        $crate::__build_method!(@post { $train_name::<$($trait_args)*>::$method::<$($turbofish)+>($($wrap)*($value), $($arg)*) }; $post)
+    };
+    (@post { $($expr:tt)* }; [await $($post:tt)*]) => {
+        $crate::__build_method!(@post { $($expr)*.await }; [$($post)*])
+    };
+    (@post { $($expr:tt)* }; [unsafe $($post:tt)*]) => {
+        $crate::__build_method!(@post { unsafe { $($expr)* } }; [$($post)*])
+    };
+    (@post { $($expr:tt)* }; [box [$($bounds:tt)*] $($post:tt)*]) => {
+        $crate::__build_method!(@post { Box::new($($expr)*) as Box<dyn $($bounds)*> }; [$($post)*])
+    };
+    (@post { $($expr:tt)* }; []) => {
+        $($expr)*
+    };
+
+    // `async fn` of a trait with `#[dispatch(async_trait)]` is rewritten into the signature `#[async_trait]` expands it to,
+    // so impls of the variants made with `#[async_trait]` match the trait, then it's built as a plain method returning the boxed future
+    ([async_trait $send:tt $ctx:tt]; $attrs:tt; async fn $name:ident; { []; [] }; ($($params:tt)*); [$( -> $output:ty)?]; $where_clause:tt; $body:tt) => {
+        $crate::__build_method!(@async_receiver { $ctx; $attrs; $name; $send; [$($output)?]; $where_clause; $body }; ($($params)*));
+    };
+    ([async_trait $send:tt $ctx:tt]; $attrs:tt; async fn $name:ident; $($rest:tt)*) => {
+        compile_error!(concat!("generic method `async fn ", stringify!($name), "` is not supported with `#[dispatch(async_trait)]`"));
+    };
+    ([async_trait $send:tt $ctx:tt]; $($rest:tt)*) => {
+        $crate::__build_method!($ctx; $($rest)*);
+    };
+    // elided lifetime of the receiver and of every reference argument gets a lifetime from the list,
+    // bound of `Self` is needed by the default implementation only, its future holds the receiver
+    (@async_receiver { $ctx:tt; $attrs:tt; $name:ident; { $($send:ident $sync:ident)? }; $($rest:tt)* }; (&$self_:ident $(, $($args:tt)*)?)) => {
+        $crate::__build_method!(@async_args { $ctx; $attrs; $name; { $($send $sync)? }; [$(+ ::core::marker::$sync)?]; $($rest)* }; ['life1 'life2 'life3 'life4 'life5 'life6 'life7 'life8 'life9 'life10 'life11 'life12 'life13 'life14 'life15]; ['life0]; [&'life0 $self_,]; { $($($args)*)? });
+    };
+    (@async_receiver { $ctx:tt; $attrs:tt; $name:ident; { $($send:ident $sync:ident)? }; $($rest:tt)* }; (&mut $self_:ident $(, $($args:tt)*)?)) => {
+        $crate::__build_method!(@async_args { $ctx; $attrs; $name; { $($send $sync)? }; [$(+ ::core::marker::$send)?]; $($rest)* }; ['life1 'life2 'life3 'life4 'life5 'life6 'life7 'life8 'life9 'life10 'life11 'life12 'life13 'life14 'life15]; ['life0]; [&'life0 mut $self_,]; { $($($args)*)? });
+    };
+    (@async_receiver { $ctx:tt; $attrs:tt; $name:ident; { $($send:ident $sync:ident)? }; $($rest:tt)* }; ($self_:ident $(, $($args:tt)*)?)) => {
+        $crate::__build_method!(@async_args { $ctx; $attrs; $name; { $($send $sync)? }; [+ ::core::marker::Sized $(+ ::core::marker::$send)?]; $($rest)* }; ['life0 'life1 'life2 'life3 'life4 'life5 'life6 'life7 'life8 'life9 'life10 'life11 'life12 'life13 'life14 'life15]; []; [$self_,]; { $($($args)*)? });
+    };
+    (@async_receiver { $ctx:tt; $attrs:tt; $name:ident; $($rest:tt)* }; ($($params:tt)*)) => {
+        compile_error!(concat!("receiver of `async fn ", stringify!($name), "` is not supported with `#[dispatch(async_trait)]`, expected `&self`, `&mut self` or `self`"));
+    };
+    (@async_args $head:tt; $pool:tt; $lifetimes:tt; [$($params:tt)*]; { mut $arg:ident: $($rest:tt)* }) => {
+        $crate::__build_method!(@async_arg $head; $pool; $lifetimes; [$($params)* mut $arg:]; { $($rest)* });
+    };
+    (@async_args $head:tt; $pool:tt; $lifetimes:tt; [$($params:tt)*]; { $arg:ident: $($rest:tt)* }) => {
+        $crate::__build_method!(@async_arg $head; $pool; $lifetimes; [$($params)* $arg:]; { $($rest)* });
+    };
+    (@async_args $head:tt; $pool:tt; $lifetimes:tt; [$($params:tt)*]; { _: $($rest:tt)* }) => {
+        $crate::__build_method!(@async_arg $head; $pool; $lifetimes; [$($params)* _:]; { $($rest)* });
+    };
+    (@async_args { $ctx:tt; $attrs:tt; $name:ident; $send:tt; $bound:tt; [$output:ty]; $where_clause:tt; $body:tt }; $pool:tt; $lifetimes:tt; $params:tt; { }) => {
+        $crate::__build_method!(@async_build { $ctx; $attrs; $name; $send; $bound; [$output]; $where_clause; $body }; $lifetimes; $params);
+    };
+    (@async_args { $ctx:tt; $attrs:tt; $name:ident; $send:tt; $bound:tt; []; $where_clause:tt; $body:tt }; $pool:tt; $lifetimes:tt; $params:tt; { }) => {
+        $crate::__build_method!(@async_build { $ctx; $attrs; $name; $send; $bound; [()]; $where_clause; $body }; $lifetimes; $params);
+    };
+    (@async_args { $ctx:tt; $attrs:tt; $name:ident; $($rest:tt)* }; $pool:tt; $lifetimes:tt; $params:tt; { $($args:tt)* }) => {
+        compile_error!(concat!("arguments of `async fn ", stringify!($name), "` are not supported with `#[dispatch(async_trait)]`, expected `name: Type`"));
+    };
+    // references with a lifetime and other types are kept as is
+    (@async_arg $head:tt; $pool:tt; $lifetimes:tt; [$($params:tt)*]; { &$lifetime:lifetime mut $arg_ty:ty $(, $($rest:tt)*)? }) => {
+        $crate::__build_method!(@async_args $head; $pool; $lifetimes; [$($params)* &$lifetime mut $arg_ty,]; { $($($rest)*)? });
+    };
+    (@async_arg $head:tt; $pool:tt; $lifetimes:tt; [$($params:tt)*]; { &$lifetime:lifetime $arg_ty:ty $(, $($rest:tt)*)? }) => {
+        $crate::__build_method!(@async_args $head; $pool; $lifetimes; [$($params)* &$lifetime $arg_ty,]; { $($($rest)*)? });
+    };
+    (@async_arg $head:tt; [$next:lifetime $($pool:lifetime)*]; [$($lifetimes:lifetime)*]; [$($params:tt)*]; { &mut $arg_ty:ty $(, $($rest:tt)*)? }) => {
+        $crate::__build_method!(@async_args $head; [$($pool)*]; [$($lifetimes)* $next]; [$($params)* &$next mut $arg_ty,]; { $($($rest)*)? });
+    };
+    (@async_arg $head:tt; [$next:lifetime $($pool:lifetime)*]; [$($lifetimes:lifetime)*]; [$($params:tt)*]; { &$arg_ty:ty $(, $($rest:tt)*)? }) => {
+        $crate::__build_method!(@async_args $head; [$($pool)*]; [$($lifetimes)* $next]; [$($params)* &$next $arg_ty,]; { $($($rest)*)? });
+    };
+    (@async_arg { $ctx:tt; $attrs:tt; $name:ident; $($rest:tt)* }; []; $lifetimes:tt; $params:tt; { & $($args:tt)* }) => {
+        compile_error!(concat!("`async fn ", stringify!($name), "` has too many references with elided lifetimes for `#[dispatch(async_trait)]`"));
+    };
+    (@async_arg $head:tt; $pool:tt; $lifetimes:tt; [$($params:tt)*]; { $arg_ty:ty $(, $($rest:tt)*)? }) => {
+        $crate::__build_method!(@async_args $head; $pool; $lifetimes; [$($params)* $arg_ty,]; { $($($rest)*)? });
+    };
+    // default implementation is run inside of the boxed future, like `#[async_trait]` does it
+    (@async_build { $ctx:tt; $attrs:tt; $name:ident; { $($send:ident $sync:ident)? }; [$($bound:tt)*]; [$output:ty]; [$(where $($where_clause:tt)*)?]; $body:block }; [$($lifetime:lifetime)*]; [$($params:tt)*]) => {
+        $crate::__build_method!($ctx; $attrs; fn $name; { [$($lifetime,)* 'async_trait]; [] }; ($($params)*);
+            [-> ::core::pin::Pin<::std::boxed::Box<dyn ::core::future::Future<Output = $output> $(+ ::core::marker::$send)? + 'async_trait>>];
+            [where $($lifetime: 'async_trait,)* Self: 'async_trait $($bound)*, $($($where_clause)*)?];
+            { ::std::boxed::Box::pin(async move { let __ret: $output = $body; __ret }) });
+    };
+    (@async_build { $ctx:tt; $attrs:tt; $name:ident; { $($send:ident $sync:ident)? }; $bound:tt; [$output:ty]; [$(where $($where_clause:tt)*)?]; ; }; [$($lifetime:lifetime)*]; [$($params:tt)*]) => {
+        $crate::__build_method!($ctx; $attrs; fn $name; { [$($lifetime,)* 'async_trait]; [] }; ($($params)*);
+            [-> ::core::pin::Pin<::std::boxed::Box<dyn ::core::future::Future<Output = $output> $(+ ::core::marker::$send)? + 'async_trait>>];
+            [where $($lifetime: 'async_trait,)* Self: 'async_trait, $($($where_clause)*)?];
+            ;);
+    };
+
+    // generated method starts with the default attributes of the enum, i.e. `#[inline]`
+    ([impl [$($(#[$var_attr:meta])* $variant:ident $pattern:tt $binding:tt),+; { $fallback:tt $associated:tt [$($inline:tt)*] }] $enum_name:ident $trait:tt]; [$($attrs:tt)*]; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs [impl [$($(#[$var_attr])* $variant $pattern $binding),+; { $fallback $associated [$($inline)*] }] $enum_name $trait]; [$($attrs)*]; [$($inline)*]; []; $($rest)*);
+    };
+    ($ctx:tt; [$($attrs:tt)*]; $($rest:tt)*) => {
+        $crate::__build_method!(@attrs $ctx; [$($attrs)*]; []; []; $($rest)*);
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+// muncher for list of methods declared on trait, every method is passed to `__build_method!` with `$ctx`
+macro_rules! __munch_methods {
+    ({ }; $ctx:tt) => {};
+
+    // `#[dispatch(async_trait)]` on the trait, every method is built in the context of the rewrite
+    ({ @async_trait $send:tt $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!({ $($rest)* }; [async_trait $send $ctx]);
+    };
+
+    // associated types are declared on the trait only, enum binds them with `with type Name = Type;`
+    ({ $(#[$($attr:tt)*])* type $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@type [$(#[$($attr)*])* type]; { $($rest)* }; $ctx);
+    };
+
+    // `impl Trait` return type is collected token by token, so it can be boxed
+    ({ $(#[$($attr:tt)*])* $($method_def:ident)+($($params:tt)*) -> impl $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@signature [$(#[$($attr)*])*] { $($method_def)+ }; { []; [] }; { ($($params)*) -> impl $($rest)* }; $ctx);
+    };
+    // `Self` can't be matched in a parsed return type, so `Self` and `Wrapper<Self, ...>` are collected token by token like `impl Trait`
+    ({ $(#[$($attr:tt)*])* $($method_def:ident)+($($params:tt)*) -> Self $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@signature [$(#[$($attr)*])*] { $($method_def)+ }; { []; [] }; { ($($params)*) -> Self $($rest)* }; $ctx);
+    };
+    ({ $(#[$($attr:tt)*])* $($method_def:ident)+($($params:tt)*) -> $wrapper:ident<Self $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@signature [$(#[$($attr)*])*] { $($method_def)+ }; { []; [] }; { ($($params)*) -> $wrapper<Self $($rest)* }; $ctx);
+    };
+    // methods are built several per step, so long traits don't hit `recursion_limit`
+    // generic methods, methods with `where` clause and other return types are built one by one below
+    // return type is matched by tokens, so `Self` stays visible to `__build_method!`, it can be a name with arguments or a shared reference to it
+    ({ $( $(#[$($attr:tt)*])* $($method_def:ident)+($($params:tt)*) $( -> $(& $($ref_lt:lifetime)?)? $return_type:ident $(<$($return_args:tt),*>)?)?; )+ }; $ctx:tt) => {
+        $( $crate::__build_method!($ctx; [$(#[$($attr)*])*]; $($method_def)+; { []; [] }; ($($params)*); [$( -> $(& $($ref_lt)?)? $return_type $(<$($return_args),*>)?)?]; []; ;); )+
+    };
+    ({
+        $(#[$($attr0:tt)*])* $($method_def0:ident)+($($params0:tt)*) $( -> $(& $($ref_lt0:lifetime)?)? $return_type0:ident $(<$($return_args0:tt),*>)?)?;
+        $(#[$($attr1:tt)*])* $($method_def1:ident)+($($params1:tt)*) $( -> $(& $($ref_lt1:lifetime)?)? $return_type1:ident $(<$($return_args1:tt),*>)?)?;
+        $(#[$($attr2:tt)*])* $($method_def2:ident)+($($params2:tt)*) $( -> $(& $($ref_lt2:lifetime)?)? $return_type2:ident $(<$($return_args2:tt),*>)?)?;
+        $(#[$($attr3:tt)*])* $($method_def3:ident)+($($params3:tt)*) $( -> $(& $($ref_lt3:lifetime)?)? $return_type3:ident $(<$($return_args3:tt),*>)?)?;
+        $($rest:tt)*
+    }; $ctx:tt) => {
+        $crate::__build_method!($ctx; [$(#[$($attr0)*])*]; $($method_def0)+; { []; [] }; ($($params0)*); [$( -> $(& $($ref_lt0)?)? $return_type0 $(<$($return_args0),*>)?)?]; []; ;);
+        $crate::__build_method!($ctx; [$(#[$($attr1)*])*]; $($method_def1)+; { []; [] }; ($($params1)*); [$( -> $(& $($ref_lt1)?)? $return_type1 $(<$($return_args1),*>)?)?]; []; ;);
+        $crate::__build_method!($ctx; [$(#[$($attr2)*])*]; $($method_def2)+; { []; [] }; ($($params2)*); [$( -> $(& $($ref_lt2)?)? $return_type2 $(<$($return_args2),*>)?)?]; []; ;);
+        $crate::__build_method!($ctx; [$(#[$($attr3)*])*]; $($method_def3)+; { []; [] }; ($($params3)*); [$( -> $(& $($ref_lt3)?)? $return_type3 $(<$($return_args3),*>)?)?]; []; ;);
+        $crate::__munch_methods!({ $($rest)* }; $ctx);
+    };
+    ({
+        $(#[$($attr0:tt)*])* $($method_def0:ident)+($($params0:tt)*) $( -> $(& $($ref_lt0:lifetime)?)? $return_type0:ident $(<$($return_args0:tt),*>)?)? $body0:block
+        $(#[$($attr1:tt)*])* $($method_def1:ident)+($($params1:tt)*) $( -> $(& $($ref_lt1:lifetime)?)? $return_type1:ident $(<$($return_args1:tt),*>)?)? $body1:block
+        $(#[$($attr2:tt)*])* $($method_def2:ident)+($($params2:tt)*) $( -> $(& $($ref_lt2:lifetime)?)? $return_type2:ident $(<$($return_args2:tt),*>)?)? $body2:block
+        $(#[$($attr3:tt)*])* $($method_def3:ident)+($($params3:tt)*) $( -> $(& $($ref_lt3:lifetime)?)? $return_type3:ident $(<$($return_args3:tt),*>)?)? $body3:block
+        $($rest:tt)*
+    }; $ctx:tt) => {
+        $crate::__build_method!($ctx; [$(#[$($attr0)*])*]; $($method_def0)+; { []; [] }; ($($params0)*); [$( -> $(& $($ref_lt0)?)? $return_type0 $(<$($return_args0),*>)?)?]; []; $body0);
+        $crate::__build_method!($ctx; [$(#[$($attr1)*])*]; $($method_def1)+; { []; [] }; ($($params1)*); [$( -> $(& $($ref_lt1)?)? $return_type1 $(<$($return_args1),*>)?)?]; []; $body1);
+        $crate::__build_method!($ctx; [$(#[$($attr2)*])*]; $($method_def2)+; { []; [] }; ($($params2)*); [$( -> $(& $($ref_lt2)?)? $return_type2 $(<$($return_args2),*>)?)?]; []; $body2);
+        $crate::__build_method!($ctx; [$(#[$($attr3)*])*]; $($method_def3)+; { []; [] }; ($($params3)*); [$( -> $(& $($ref_lt3)?)? $return_type3 $(<$($return_args3),*>)?)?]; []; $body3);
+        $crate::__munch_methods!({ $($rest)* }; $ctx);
+    };
+    ({
+        $(#[$($attr0:tt)*])* $($method_def0:ident)+($($params0:tt)*) $( -> $(& $($ref_lt0:lifetime)?)? $return_type0:ident $(<$($return_args0:tt),*>)?)?;
+        $(#[$($attr1:tt)*])* $($method_def1:ident)+($($params1:tt)*) $( -> $(& $($ref_lt1:lifetime)?)? $return_type1:ident $(<$($return_args1:tt),*>)?)? $body1:block
+        $($rest:tt)*
+    }; $ctx:tt) => {
+        $crate::__build_method!($ctx; [$(#[$($attr0)*])*]; $($method_def0)+; { []; [] }; ($($params0)*); [$( -> $(& $($ref_lt0)?)? $return_type0 $(<$($return_args0),*>)?)?]; []; ;);
+        $crate::__build_method!($ctx; [$(#[$($attr1)*])*]; $($method_def1)+; { []; [] }; ($($params1)*); [$( -> $(& $($ref_lt1)?)? $return_type1 $(<$($return_args1),*>)?)?]; []; $body1);
+        $crate::__munch_methods!({ $($rest)* }; $ctx);
+    };
+    ({
+        $(#[$($attr0:tt)*])* $($method_def0:ident)+($($params0:tt)*) $( -> $(& $($ref_lt0:lifetime)?)? $return_type0:ident $(<$($return_args0:tt),*>)?)? $body0:block
+        $(#[$($attr1:tt)*])* $($method_def1:ident)+($($params1:tt)*) $( -> $(& $($ref_lt1:lifetime)?)? $return_type1:ident $(<$($return_args1:tt),*>)?)?;
+        $($rest:tt)*
+    }; $ctx:tt) => {
+        $crate::__build_method!($ctx; [$(#[$($attr0)*])*]; $($method_def0)+; { []; [] }; ($($params0)*); [$( -> $(& $($ref_lt0)?)? $return_type0 $(<$($return_args0),*>)?)?]; []; $body0);
+        $crate::__build_method!($ctx; [$(#[$($attr1)*])*]; $($method_def1)+; { []; [] }; ($($params1)*); [$( -> $(& $($ref_lt1)?)? $return_type1 $(<$($return_args1),*>)?)?]; []; ;);
+        $crate::__munch_methods!({ $($rest)* }; $ctx);
+    };
+    // variant without block
+    ({ $(#[$($attr:tt)*])* $($method_def:ident)+($($params:tt)*) $( -> $return_type:ty)?; $($rest:tt)* }; $ctx:tt) => {
+        $crate::__build_method!($ctx; [$(#[$($attr)*])*]; $($method_def)+; { []; [] }; ($($params)*); [$( -> $return_type)?]; []; ;);
+        $crate::__munch_methods!({ $($rest)* }; $ctx);
+    };
+    // variant with block
+    ({ $(#[$($attr:tt)*])* $($method_def:ident)+($($params:tt)*) $( -> $return_type:ty)? $body:block $($rest:tt)* }; $ctx:tt) => {
+        $crate::__build_method!($ctx; [$(#[$($attr)*])*]; $($method_def)+; { []; [] }; ($($params)*); [$( -> $return_type)?]; []; $body);
+        $crate::__munch_methods!({ $($rest)* }; $ctx);
+    };
+    // variant with `where` clause
+    ({ $(#[$($attr:tt)*])* $($method_def:ident)+($($params:tt)*) $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@signature [$(#[$($attr)*])*] { $($method_def)+ }; { []; [] }; { ($($params)*) $($rest)* }; $ctx);
+    };
+    // generic method, generic parameters are collected token by token
+    ({ $(#[$($attr:tt)*])* $($method_def:ident)+ < $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generic_param [$(#[$($attr)*])*] { $($method_def)+ }; []; []; { $($rest)* }; $ctx);
+    };
+
+    // start of generic parameter, names of type and const parameters are collected for turbofish
+    (@generic_param $attrs:tt $head:tt; [$($generics:tt)*]; [$($turbofish:tt)*]; { $lifetime:lifetime $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generics $attrs $head; [$($generics)* $lifetime]; []; [$($turbofish)*]; { $($rest)* }; $ctx);
+    };
+    (@generic_param $attrs:tt $head:tt; [$($generics:tt)*]; [$($turbofish:tt)*]; { const $param:ident $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generics $attrs $head; [$($generics)* const $param]; []; [$($turbofish)* $param,]; { $($rest)* }; $ctx);
+    };
+    (@generic_param $attrs:tt $head:tt; [$($generics:tt)*]; [$($turbofish:tt)*]; { $param:ident $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generics $attrs $head; [$($generics)* $param]; []; [$($turbofish)* $param,]; { $($rest)* }; $ctx);
+    };
+    (@generic_param $attrs:tt $head:tt; $generics:tt; $turbofish:tt; { $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generics $attrs $head; $generics; []; $turbofish; { $($rest)* }; $ctx);
+    };
+
+    // `depth` holds a `<` for every unclosed angle bracket inside of generic parameters
+    (@generics $attrs:tt $head:tt; [$($generics:tt)*]; []; $turbofish:tt; { > $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@signature $attrs $head; { [$($generics)*]; $turbofish }; { $($rest)* }; $ctx);
+    };
+    (@generics $attrs:tt $head:tt; [$($generics:tt)*]; [<]; $turbofish:tt; { >> $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@signature $attrs $head; { [$($generics)* >]; $turbofish }; { $($rest)* }; $ctx);
+    };
+    (@generics $attrs:tt $head:tt; [$($generics:tt)*]; []; $turbofish:tt; { , $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generic_param $attrs $head; [$($generics)* ,]; $turbofish; { $($rest)* }; $ctx);
+    };
+    (@generics $attrs:tt $head:tt; [$($generics:tt)*]; [$($depth:tt)*]; $turbofish:tt; { < $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generics $attrs $head; [$($generics)* <]; [$($depth)* <]; $turbofish; { $($rest)* }; $ctx);
+    };
+    (@generics $attrs:tt $head:tt; [$($generics:tt)*]; [< $($depth:tt)*]; $turbofish:tt; { > $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generics $attrs $head; [$($generics)* >]; [$($depth)*]; $turbofish; { $($rest)* }; $ctx);
+    };
+    (@generics $attrs:tt $head:tt; [$($generics:tt)*]; [< < $($depth:tt)*]; $turbofish:tt; { >> $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generics $attrs $head; [$($generics)* >>]; [$($depth)*]; $turbofish; { $($rest)* }; $ctx);
+    };
+    // defaults of generic parameters are allowed on the trait only, so they are skipped for the generated method
+    (@generics $attrs:tt $head:tt; [$($generics:tt)*]; []; $turbofish:tt; { = $($rest:tt)* }; [trait]) => {
+        $crate::__munch_methods!(@generics $attrs $head; [$($generics)* =]; []; $turbofish; { $($rest)* }; [trait]);
+    };
+    (@generics $attrs:tt $head:tt; $generics:tt; []; $turbofish:tt; { = $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generic_default $attrs $head; $generics; []; $turbofish; { $($rest)* }; $ctx);
+    };
+    (@generics $attrs:tt $head:tt; [$($generics:tt)*]; $depth:tt; $turbofish:tt; { $next:tt $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generics $attrs $head; [$($generics)* $next]; $depth; $turbofish; { $($rest)* }; $ctx);
+    };
+    (@generic_default $attrs:tt $head:tt; $generics:tt; []; $turbofish:tt; { , $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generics $attrs $head; $generics; []; $turbofish; { , $($rest)* }; $ctx);
+    };
+    (@generic_default $attrs:tt $head:tt; $generics:tt; []; $turbofish:tt; { > $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generics $attrs $head; $generics; []; $turbofish; { > $($rest)* }; $ctx);
+    };
+    (@generic_default $attrs:tt $head:tt; $generics:tt; [<]; $turbofish:tt; { >> $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generics $attrs $head; $generics; []; $turbofish; { > $($rest)* }; $ctx);
+    };
+    (@generic_default $attrs:tt $head:tt; $generics:tt; [$($depth:tt)*]; $turbofish:tt; { < $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generic_default $attrs $head; $generics; [$($depth)* <]; $turbofish; { $($rest)* }; $ctx);
+    };
+    (@generic_default $attrs:tt $head:tt; $generics:tt; [< $($depth:tt)*]; $turbofish:tt; { > $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generic_default $attrs $head; $generics; [$($depth)*]; $turbofish; { $($rest)* }; $ctx);
+    };
+    (@generic_default $attrs:tt $head:tt; $generics:tt; [< < $($depth:tt)*]; $turbofish:tt; { >> $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generic_default $attrs $head; $generics; [$($depth)*]; $turbofish; { $($rest)* }; $ctx);
+    };
+    (@generic_default $attrs:tt $head:tt; $generics:tt; $depth:tt; $turbofish:tt; { $next:tt $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@generic_default $attrs $head; $generics; $depth; $turbofish; { $($rest)* }; $ctx);
+    };
+
+    (@signature $attrs:tt $head:tt; $generics:tt; { ($($params:tt)*) -> impl $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@impl_return $attrs $head; $generics; ($($params)*); [-> impl]; { $($rest)* }; $ctx);
+    };
+    (@signature $attrs:tt $head:tt; $generics:tt; { ($($params:tt)*) -> Self $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@impl_return $attrs $head; $generics; ($($params)*); [-> Self]; { $($rest)* }; $ctx);
+    };
+    (@signature $attrs:tt $head:tt; $generics:tt; { ($($params:tt)*) -> $wrapper:ident<Self $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@impl_return $attrs $head; $generics; ($($params)*); [-> $wrapper<Self]; { $($rest)* }; $ctx);
+    };
+    (@signature $attrs:tt { $($method_def:ident)+ }; $generics:tt; { ($($params:tt)*) $( -> $return_type:ty)?; $($rest:tt)* }; $ctx:tt) => {
+        $crate::__build_method!($ctx; $attrs; $($method_def)+; $generics; ($($params)*); [$( -> $return_type)?]; []; ;);
+        $crate::__munch_methods!({ $($rest)* }; $ctx);
+    };
+    (@signature $attrs:tt { $($method_def:ident)+ }; $generics:tt; { ($($params:tt)*) $( -> $return_type:ty)? $body:block $($rest:tt)* }; $ctx:tt) => {
+        $crate::__build_method!($ctx; $attrs; $($method_def)+; $generics; ($($params)*); [$( -> $return_type)?]; []; $body);
+        $crate::__munch_methods!({ $($rest)* }; $ctx);
+    };
+    (@signature $attrs:tt $head:tt; $generics:tt; { ($($params:tt)*) $( -> $return_type:ty)? where $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@where $attrs $head; $generics; ($($params)*); [$( -> $return_type)?]; [where]; { $($rest)* }; $ctx);
+    };
+
+    // `impl Trait` bounds and `Self` return types are collected token by token until the end of the method
+    (@impl_return $attrs:tt $head:tt; $generics:tt; $params:tt; $ret:tt; { where $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@where $attrs $head; $generics; $params; $ret; [where]; { $($rest)* }; $ctx);
+    };
+    (@impl_return $attrs:tt { $($method_def:ident)+ }; $generics:tt; $params:tt; $ret:tt; { ; $($rest:tt)* }; $ctx:tt) => {
+        $crate::__build_method!($ctx; $attrs; $($method_def)+; $generics; $params; $ret; []; ;);
+        $crate::__munch_methods!({ $($rest)* }; $ctx);
+    };
+    (@impl_return $attrs:tt { $($method_def:ident)+ }; $generics:tt; $params:tt; $ret:tt; { $body:block $($rest:tt)* }; $ctx:tt) => {
+        $crate::__build_method!($ctx; $attrs; $($method_def)+; $generics; $params; $ret; []; $body);
+        $crate::__munch_methods!({ $($rest)* }; $ctx);
+    };
+    (@impl_return $attrs:tt $head:tt; $generics:tt; $params:tt; [$($ret:tt)*]; { $next:tt $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@impl_return $attrs $head; $generics; $params; [$($ret)* $next]; { $($rest)* }; $ctx);
+    };
+
+    (@type [$($item:tt)*]; { ; $($rest:tt)* }; [trait]) => {
+        $($item)*;
+        $crate::__munch_methods!({ $($rest)* }; [trait]);
+    };
+    (@type [$($item:tt)*]; { ; $($rest:tt)* }; [async_trait $send:tt [trait]]) => {
+        $($item)*;
+        $crate::__munch_methods!({ $($rest)* }; [async_trait $send [trait]]);
+    };
+    (@type $item:tt; { ; $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!({ $($rest)* }; $ctx);
+    };
+    (@type [$($item:tt)*]; { $next:tt $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@type [$($item)* $next]; { $($rest)* }; $ctx);
+    };
+
+    // `where` predicates are collected token by token until the end of the method
+    (@where $attrs:tt { $($method_def:ident)+ }; $generics:tt; $params:tt; $ret:tt; $where_clause:tt; { ; $($rest:tt)* }; $ctx:tt) => {
+        $crate::__build_method!($ctx; $attrs; $($method_def)+; $generics; $params; $ret; $where_clause; ;);
+        $crate::__munch_methods!({ $($rest)* }; $ctx);
+    };
+    (@where $attrs:tt { $($method_def:ident)+ }; $generics:tt; $params:tt; $ret:tt; $where_clause:tt; { $body:block $($rest:tt)* }; $ctx:tt) => {
+        $crate::__build_method!($ctx; $attrs; $($method_def)+; $generics; $params; $ret; $where_clause; $body);
+        $crate::__munch_methods!({ $($rest)* }; $ctx);
+    };
+    (@where $attrs:tt $head:tt; $generics:tt; $params:tt; $ret:tt; [$($where_clause:tt)*]; { $next:tt $($rest:tt)* }; $ctx:tt) => {
+        $crate::__munch_methods!(@where $attrs $head; $generics; $params; $ret; [$($where_clause)* $next]; { $($rest)* }; $ctx);
+    };
+}
+
+#[macro_export]
+macro_rules! enum_dispatch {
+    // every variant is normalized to a record
+    // `{ [attributes] [cfg attributes] Variant [(field types)] (pattern) binding [predicate] [newtype type] [dispatched type] [options] }`
+    // `pattern` binds the field implementing the trait to `binding`, others are ignored
+    // unit variants match `{}` and use the unit struct with the same name as `[binding]`
+    // only `cfg` attributes are copied from the variant to the generated items
+    (@generate $head:tt; [$($record:tt)+]) => {
+        $crate::enum_dispatch!(@definition $head; [$($record)+]);
+        $(
+            $crate::enum_dispatch!(@conversions $head; $record);
+        )+
+    };
+    (@definition { $kind:tt { [$($fallback:tt)*] $associated:tt $inline:tt [$($forward:ident)*] $generate:tt $visitor:tt $match_macro:tt $derives:tt } $(#[$enum_attr:meta])* $enum_vis:vis enum $enum_name:ident [$($lts:tt)*] [$($params:tt)*] [$($args:tt)*] [$($where_clause:tt)*]; $traits:tt; $assoc:tt }; [$({ [$(#[$var_attr:meta])*] [$(#[$var_cfg:meta])*] $variant:ident [$($fields:tt)*] $pattern:tt $binding:tt $is_variant:tt $newtype:tt $dispatched:tt $options:tt })+]) => {
+        $crate::enum_dispatch!(@enum_definition $kind {
+            $(#[$enum_attr])*
+            $enum_vis enum $enum_name<$($lts)* $($params)*> $($where_clause)* {
+                $($(#[$var_attr])* $variant $($fields)*),+
+            }
+        });
+
+        $crate::enum_dispatch!(@impls { $enum_vis $enum_name [$($lts)*] [$($params)*] [$($args)*] [$($where_clause)*] $assoc $generate }; [$($(#[$var_cfg])* $variant $pattern $binding),+; { [$($fallback)*] $associated $inline }]; $traits);
+
+        impl<$($lts)* $($params)*> $enum_name<$($args)*> $($where_clause)* {
+            $crate::enum_dispatch!(@if_generated variant_names $generate {
+                /// Names of the variants in order of declaration
+                #[allow(dead_code)]
+                $enum_vis const VARIANTS: &'static [&'static str] = &[$($(#[$var_cfg])* stringify!($variant)),+];
+
+                /// Name of the current variant
+                #[allow(dead_code)]
+                $enum_vis fn variant_name(&self) -> &'static str {
+                    match self {
+                        $(
+                            $(#[$var_cfg])*
+                            $enum_name::$variant { .. } => stringify!($variant),
+                        )+
+                        $($fallback)*
+                    }
+                }
+            });
+
+            $crate::enum_dispatch!(@if_generated discriminant_index $generate {
+                /// Index of the current variant in order of declaration, variants disabled with `#[cfg(...)]` are counted too
+                #[allow(dead_code)]
+                $enum_vis fn discriminant_index(&self) -> usize {
+                    #[allow(dead_code, non_camel_case_types)]
+                    enum Index {
+                        $($variant),+
+                    }
+
+                    match self {
+                        $(
+                            $(#[$var_cfg])*
+                            $enum_name::$variant { .. } => Index::$variant as usize,
+                        )+
+                        $($fallback)*
+                    }
+                }
+            });
+
+            $crate::enum_dispatch!(@if_generated as_ref $generate {
+                /// Reference to the inner value if it has type `Inner`
+                #[allow(dead_code)]
+                $enum_vis fn as_variant<'__dispatch, Inner>(&'__dispatch self) -> Option<&'__dispatch Inner> where &'__dispatch Inner: TryFrom<&'__dispatch Self> {
+                    <&'__dispatch Inner>::try_from(self).ok()
+                }
+
+                /// Mutable reference to the inner value if it has type `Inner`
+                #[allow(dead_code)]
+                $enum_vis fn as_variant_mut<'__dispatch, Inner>(&'__dispatch mut self) -> Option<&'__dispatch mut Inner> where &'__dispatch mut Inner: TryFrom<&'__dispatch mut Self> {
+                    <&'__dispatch mut Inner>::try_from(self).ok()
+                }
+            });
+        }
+
+        $crate::enum_dispatch!(@if_generated variant_names $generate {
+            impl<$($lts)* $($params)*> $crate::VariantName for $enum_name<$($args)*> $($where_clause)* {
+                #[inline]
+                fn variant_name(&self) -> &'static str {
+                    Self::variant_name(self)
+                }
+            }
+        });
+
+        $crate::enum_dispatch!(@forwards [$($forward)*]; { $enum_name [$($lts)* $($params)*] [$($args)*] [$($where_clause)*] }; [$($(#[$var_cfg])* $variant $pattern $binding),+; [$($fallback)*]]; [$({ [$(#[$var_cfg])*] $dispatched })+]);
+
+        $crate::enum_dispatch!(@default { $enum_name [$($lts)* $($params)*] [$($args)*] [$($where_clause)*] }; []; [$({ [$(#[$var_cfg])*] $variant [$($fields)*] $options })+]);
+
+        $crate::enum_dispatch!(@visitor $visitor; { $enum_vis $enum_name [$($lts)* $($params)*] [$($args)*] [$($where_clause)*] }; [$({ [$(#[$var_cfg])*] $variant $pattern $binding $dispatched $options })+]; [$($fallback)*]);
+
+        $crate::enum_dispatch!(@match_macro $match_macro; ($); $enum_name; [$({ [$(#[$var_cfg])*] $variant $pattern $binding })+]; [$($fallback)*]);
+
+        $crate::enum_dispatch!(@derives $derives; []; { $enum_name [$($lts)*] [$($params)*] [$($args)*] [$($where_clause)*] }; [$({ [$(#[$var_cfg])*] $variant [$($fields)*] })+]);
+    };
+
+    // derived traits are picked from paths in `#[derive(...)]` by their last segment, other derives are not checked
+    // fields are checked with a bound of a dummy function, so the error points to the field type of the variant
+    // derives add bounds on type parameters, which can't be repeated for the check, so generic enums aren't supported
+    (@derives [[] $derived:tt]; $found:tt; $enum:tt; $records:tt) => {};
+    (@derives [$check:tt [Clone $($rest:tt)*]]; [$($found:tt)*]; $enum:tt; $records:tt) => {
+        $crate::enum_dispatch!(@derives [$check [$($rest)*]]; [$($found)* + Clone]; $enum; $records);
+    };
+    (@derives [$check:tt [Debug $($rest:tt)*]]; [$($found:tt)*]; $enum:tt; $records:tt) => {
+        $crate::enum_dispatch!(@derives [$check [$($rest)*]]; [$($found)* + ::core::fmt::Debug]; $enum; $records);
+    };
+    (@derives [$check:tt [PartialEq $($rest:tt)*]]; [$($found:tt)*]; $enum:tt; $records:tt) => {
+        $crate::enum_dispatch!(@derives [$check [$($rest)*]]; [$($found)* + PartialEq]; $enum; $records);
+    };
+    (@derives [$check:tt [Hash $($rest:tt)*]]; [$($found:tt)*]; $enum:tt; $records:tt) => {
+        $crate::enum_dispatch!(@derives [$check [$($rest)*]]; [$($found)* + ::core::hash::Hash]; $enum; $records);
+    };
+    (@derives [$check:tt [$token:tt $($rest:tt)*]]; $found:tt; $enum:tt; $records:tt) => {
+        $crate::enum_dispatch!(@derives [$check [$($rest)*]]; $found; $enum; $records);
+    };
+    (@derives [$check:tt []]; []; $enum:tt; $records:tt) => {};
+    (@derives [$check:tt []]; $found:tt; { $enum_name:ident $lts:tt [$($params:tt)+] $args:tt $where_clause:tt }; $records:tt) => {
+        compile_error!(concat!("`check_derives` doesn't support enum `", stringify!($enum_name), "` with type or const parameters"));
+    };
+    (@derives [$check:tt []]; $found:tt; $enum:tt; [$({ $cfgs:tt $variant:ident $fields:tt })+]) => {
+        $(
+            $crate::enum_dispatch!(@derived $cfgs; $found; $enum; $fields);
+        )+
+    };
+    (@derived [$(#[$var_cfg:meta])*]; [+ $($found:tt)+]; { $enum_name:ident [$($lts:tt)*] [] [$($args:tt)*] [$($where_clause:tt)*] }; [($($field:ty),* $(,)?) $($discriminant:tt)*]) => {
+        $(#[$var_cfg])*
+        const _: () = {
+            trait Derived {
+                fn derived<Field: $($found)+>() {}
+
+                fn variant();
+            }
+
+            impl<$($lts)*> Derived for $enum_name<$($args)*> $($where_clause)* {
+                fn variant() {
+                    $(<Self as Derived>::derived::<$field>();)*
+                }
+            }
+        };
+    };
+    (@derived $cfgs:tt; $found:tt; $enum:tt; $unit:tt) => {};
+
+    // `$` can't be written in the generated macro directly, so it is passed as `$d`
+    // macro is defined by `macro_rules!`, so it can be used after the enum in the same module and the enum has to be in scope
+    (@match_macro []; $d:tt; $enum_name:ident; $records:tt; $fallback:tt) => {};
+    (@match_macro [$name:ident]; ($d:tt); $enum_name:ident; [$({ [$(#[$var_cfg:meta])*] $variant:ident $pattern:tt $binding:tt })+]; [$($fallback:tt)*]) => {
+        #[allow(unused_macros)]
+        macro_rules! $name {
+            ($d value:expr, |$d inner:pat_param| $d body:expr $d(,)?) => {
+                match $d value {
+                    $(
+                        $(#[$var_cfg])*
+                        $enum_name::$variant $pattern => {
+                            let $d inner = $crate::enum_dispatch!(@match_value $binding);
+                            $d body
+                        }
+                    )+
+                    $($fallback)*
+                }
+            };
+        }
+    };
+    // unit variant has no value, so fresh unit struct is bound
+    (@match_value [$unit:ident]) => {
+        $unit
+    };
+    (@match_value $value:tt) => {
+        $value
+    };
+
+    // visitor methods are named with `#[dispatch(visit = name)]` of the variant or after the variant itself,
+    // names like `visit_square` can't be built by `macro_rules!`
+    // value reached by `via` accessor has unknown type, so there is no argument type for its visitor method
+    (@visitor []; $enum:tt; $records:tt; $fallback:tt) => {};
+    (@visitor [$name:ident]; $enum:tt; $records:tt; $fallback:tt) => {
+        $crate::enum_dispatch!(@visitor_methods $name; $enum; []; $records; $fallback);
+    };
+    (@visitor_methods $name:ident; $enum:tt; $done:tt; [{ $cfgs:tt $variant:ident $pattern:tt $binding:tt [] $options:tt } $($rest:tt)*]; $fallback:tt) => {
+        compile_error!(concat!("variant `", stringify!($variant), "` with `via` accessor can't be visited by `", stringify!($name), "`"));
+    };
+    (@visitor_methods $name:ident; $enum:tt; [$($done:tt)*]; [{ $cfgs:tt $variant:ident $pattern:tt $binding:tt $dispatched:tt [{ visit $method:ident } $($options:tt)*] } $($rest:tt)*]; $fallback:tt) => {
+        $crate::enum_dispatch!(@visitor_methods $name; $enum; [$($done)* { $cfgs $variant $pattern $binding $dispatched $method }]; [$($rest)*]; $fallback);
+    };
+    (@visitor_methods $name:ident; $enum:tt; $done:tt; [{ $cfgs:tt $variant:ident $pattern:tt $binding:tt $dispatched:tt [$option:tt $($options:tt)*] } $($rest:tt)*]; $fallback:tt) => {
+        $crate::enum_dispatch!(@visitor_methods $name; $enum; $done; [{ $cfgs $variant $pattern $binding $dispatched [$($options)*] } $($rest)*]; $fallback);
+    };
+    (@visitor_methods $name:ident; $enum:tt; [$($done:tt)*]; [{ $cfgs:tt $variant:ident $pattern:tt $binding:tt $dispatched:tt [] } $($rest:tt)*]; $fallback:tt) => {
+        $crate::enum_dispatch!(@visitor_methods $name; $enum; [$($done)* { $cfgs $variant $pattern $binding $dispatched $variant }]; [$($rest)*]; $fallback);
+    };
+    (@visitor_methods $name:ident; { $enum_vis:vis $enum_name:ident [$($generics:tt)*] [$($args:tt)*] [$($where_clause:tt)*] }; [$({ [$(#[$var_cfg:meta])*] $variant:ident $pattern:tt $binding:tt [$variant_type:ty] $method:ident })+]; []; [$($fallback:tt)*]) => {
+        #[doc = concat!("Visitor of the variants of [`", stringify!($enum_name), "`], methods do nothing by default")]
+        $enum_vis trait $name<$($generics)*> $($where_clause)* {
+            $(
+                $(#[$var_cfg])*
+                #[doc = concat!("Called with the value of `", stringify!($variant), "` variant")]
+                #[allow(non_snake_case)]
+                fn $method(&mut self, value: &$variant_type) {
+                    let _ = value;
+                }
+            )+
+        }
+
+        impl<$($generics)*> $enum_name<$($args)*> $($where_clause)* {
+            #[doc = concat!("Calls method of `", stringify!($name), "` for the current variant")]
+            #[allow(dead_code)]
+            $enum_vis fn visit<Visitor: $name<$($args)*> + ?Sized>(&self, visitor: &mut Visitor) {
+                match self {
+                    $(
+                        $(#[$var_cfg])*
+                        $enum_name::$variant $pattern => visitor.$method($crate::enum_dispatch!(@dyn_value $binding)),
+                    )+
+                    $($fallback)*
+                }
+            }
+        }
+    };
+
+    // std traits are implemented with the same variants as dispatched ones
+    (@forwards [$($forward:ident)*]; $enum:tt; $variants:tt; $types:tt) => {
+        $(
+            $crate::enum_dispatch!(@forward $forward; $enum; $variants; $types);
+        )*
+    };
+    (@forward display; { $enum_name:ident [$($generics:tt)*] [$($args:tt)*] [$($where_clause:tt)*] }; [$($(#[$var_attr:meta])* $variant:ident $pattern:tt $binding:tt),+; [$($fallback:tt)*]]; $types:tt) => {
+        impl<$($generics)*> ::core::fmt::Display for $enum_name<$($args)*> $($where_clause)* {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self {
+                    $(
+                        $(#[$var_attr])*
+                        $enum_name::$variant $pattern => ::core::fmt::Display::fmt($crate::enum_dispatch!(@dyn_value $binding), f),
+                    )+
+                    $($fallback)*
+                }
+            }
+        }
+    };
+
+    // values of different variants are not equal, types of the values are bound instead of generic parameters of the enum
+    // bounds of variants disabled with `#[cfg(...)]` and of values from `via` accessors are left out
+    (@forward partial_eq; $enum:tt; $variants:tt; $types:tt) => {
+        $crate::enum_dispatch!(@partial_eq $enum; $variants; []; $types);
+    };
+    (@partial_eq $enum:tt; $variants:tt; [$($bounds:tt)*]; [{ [] [$variant_type:ty] } $($types:tt)*]) => {
+        $crate::enum_dispatch!(@partial_eq $enum; $variants; [$($bounds)* $variant_type: PartialEq,]; [$($types)*]);
+    };
+    (@partial_eq $enum:tt; $variants:tt; $bounds:tt; [{ $cfgs:tt $dispatched:tt } $($types:tt)*]) => {
+        $crate::enum_dispatch!(@partial_eq $enum; $variants; $bounds; [$($types)*]);
+    };
+    (@partial_eq { $enum_name:ident [$($generics:tt)*] [$($args:tt)*] [$(where $($where_clause:tt)*)?] }; [$($(#[$var_attr:meta])* $variant:ident $pattern:tt $binding:tt),+; [$($fallback:tt)*]]; [$($bounds:tt)*]; []) => {
+        impl<$($generics)*> PartialEq for $enum_name<$($args)*> where $($bounds)* $($($where_clause)*)? {
+            fn eq(&self, other: &Self) -> bool {
+                match self {
+                    $(
+                        $(#[$var_attr])*
+                        $enum_name::$variant $pattern => {
+                            let value = $crate::enum_dispatch!(@dyn_value $binding);
+                            #[allow(unreachable_patterns)]
+                            match other {
+                                $enum_name::$variant $pattern => value == $crate::enum_dispatch!(@dyn_value $binding),
+                                _ => false,
+                            }
+                        }
+                    )+
+                    $($fallback)*
+                }
+            }
+        }
+    };
+
+    // variant marked with `#[dispatch(default)]` is the default value of the enum, its fields are `Default` too
+    (@default $enum:tt; []; []) => {};
+    (@default { $enum_name:ident [$($generics:tt)*] [$($args:tt)*] [$($where_clause:tt)*] }; [[$(#[$var_cfg:meta])*] $variant:ident [$($fields:tt)*]]; []) => {
+        $(#[$var_cfg])*
+        impl<$($generics)*> Default for $enum_name<$($args)*> $($where_clause)* {
+            fn default() -> Self {
+                $crate::enum_dispatch!(@default_value $enum_name::$variant $($fields)*)
+            }
+        }
+    };
+    (@default $enum:tt; [$($found:tt)+]; [{ $cfgs:tt $variant:ident $fields:tt [default $($options:tt)*] } $($records:tt)*]) => {
+        compile_error!(concat!("only one variant can be marked with `#[dispatch(default)]`, `", stringify!($variant), "` is the second one"));
+    };
+    (@default $enum:tt; []; [{ $cfgs:tt $variant:ident $fields:tt [default $($options:tt)*] } $($records:tt)*]) => {
+        $crate::enum_dispatch!(@default $enum; [$cfgs $variant $fields]; [{ $cfgs $variant $fields [$($options)*] } $($records)*]);
+    };
+    (@default $enum:tt; $found:tt; [{ $cfgs:tt $variant:ident $fields:tt [$option:tt $($options:tt)*] } $($records:tt)*]) => {
+        $crate::enum_dispatch!(@default $enum; $found; [{ $cfgs $variant $fields [$($options)*] } $($records)*]);
+    };
+    (@default $enum:tt; $found:tt; [{ $cfgs:tt $variant:ident $fields:tt [] } $($records:tt)*]) => {
+        $crate::enum_dispatch!(@default $enum; $found; [$($records)*]);
+    };
+    (@default_value $enum_name:ident::$variant:ident $(= $discriminant:expr)?) => {
+        $enum_name::$variant
+    };
+    (@default_value $enum_name:ident::$variant:ident ($field:ty) $(= $discriminant:expr)?) => {
+        $enum_name::$variant(<$field as Default>::default())
+    };
+    (@default_value $enum_name:ident::$variant:ident ($($field:ty,)*) $(= $discriminant:expr)?) => {
+        $enum_name::$variant($(<$field as Default>::default()),*)
+    };
+
+    // enum declared with `existing` is defined outside of the macro
+    (@enum_definition [existing] $definition:tt) => {};
+    (@enum_definition [] { $($definition:tt)* }) => {
+        $($definition)*
+    };
+
+    // every trait is implemented with the same variants, associated types after the enum are bound for every trait
+    // single trait is called with method syntax, so wrappers of the variant type are dereferenced, e.g. `Arc<Rect>`
+    // methods of several traits are called with fully qualified path, so traits can have methods with the same name
+    (@impls $enum:tt; $variants:tt; [$trait:tt]) => {
+        $crate::enum_dispatch!(@impl $enum; $variants; [method]; $trait);
+    };
+    (@impls $enum:tt; $variants:tt; [$($trait:tt)+]) => {
+        $(
+            $crate::enum_dispatch!(@impl $enum; $variants; [qualified]; $trait);
+        )+
+    };
+    // `where` clause of the trait ends with a comma, so it is followed by `where` clause of the enum
+    (@impl $enum:tt; $variants:tt; $call:tt; { $train_name:ident $trait_lts:tt $trait_params:tt $trait_args:tt $trait_where:tt $impl_attrs:tt $supertraits:tt [$($import:tt)+]; $($trait:tt)* }) => {
+        const _: () = {
+            $($import)+
+
+            $crate::enum_dispatch!(@impl $enum; $variants; $call; { $train_name $trait_lts $trait_params $trait_args $trait_where $impl_attrs $supertraits []; $($trait)* });
+        };
+    };
+    (@impl { $enum_vis:vis $enum_name:ident [$($lts:tt)*] [$($params:tt)*] [$($args:tt)*] [$(where $($where_clause:tt)*)?] [$($assoc:tt)*] $generate:tt }; $variants:tt; $call:tt; { $train_name:ident [$($trait_lts:tt)*] [$($trait_params:tt)*] [$($trait_args:tt)*] [$(where $($trait_where:tt)*)?] [$($impl_attr:tt)*] $supertraits:tt []; $methods:tt; [$($trait_assoc:tt)*]; [$($option:tt)*] }) => {
+        $crate::enum_dispatch!(@if_generated dispatch $generate {
+            $($impl_attr)*
+            impl<$($trait_lts)* $($lts)* $($trait_params)* $($params)*> $train_name<$($trait_args)*> for $enum_name<$($args)*> where $($($trait_where)*)? $($($where_clause)*)? {
+                $($trait_assoc)*
+                $($assoc)*
+                $crate::__munch_methods!($methods; [impl $variants $enum_name [$train_name [$($trait_args)*] $call]]);
+            }
+        });
+
+        $crate::enum_dispatch!(@trait_options [$($option)*]; { $enum_vis $enum_name [$($lts)*] [$($params)*] [$($args)*] [$(where $($where_clause)*)?] }; $variants; { $train_name [$($trait_lts)*] [$($trait_params)*] [$($trait_args)*] [$(where $($trait_where)*)?] [$($trait_assoc)* $($assoc)*] });
+    };
+    (@trait_options [$($option:tt)*]; $enum:tt; $variants:tt; $trait:tt) => {
+        $(
+            $crate::enum_dispatch!(@trait_option $option; $enum; $variants; $trait);
+        )*
+    };
+
+    // trait object borrows the variant value, associated types are bound to the types chosen for the enum
+    // unit struct is promoted to a constant, so it can be borrowed for any lifetime
+    (@trait_option { as_dyn $name:ident }; { $enum_vis:vis $enum_name:ident [$($lts:tt)*] [$($params:tt)*] [$($args:tt)*] [$($where_clause:tt)*] }; [$($(#[$var_attr:meta])* $variant:ident $pattern:tt $binding:tt),+; { [$($fallback:tt)*] $associated:tt $inline:tt }]; { $train_name:ident [$($trait_lts:tt)*] [$($trait_params:tt)*] [$($trait_args:tt)*] [$($trait_where:tt)*] [$(type $assoc_name:ident = $assoc_type:ty;)*] }) => {
+        impl<$($lts)* $($params)*> $enum_name<$($args)*> $($where_clause)* {
+            /// Inner value as a trait object
+            #[allow(dead_code)]
+            $enum_vis fn $name<$($trait_lts)* $($trait_params)*>(&self) -> &dyn $train_name<$($trait_args)* $($assoc_name = $assoc_type,)*> $($trait_where)* {
+                match self {
+                    $(
+                        $(#[$var_attr])*
+                        $enum_name::$variant $pattern => $crate::enum_dispatch!(@dyn_value $binding),
+                    )+
+                    $($fallback)*
+                }
+            }
+        }
+    };
+    // `via` accessors may reach the value through a shared reference only, so mutable and owned trait objects are skipped if any variant has one
+    (@trait_option { as_dyn_mut $name:ident }; $enum:tt; [$($variants:tt)*]; $trait:tt) => {
+        $crate::enum_dispatch!(@without_via { as_dyn_mut $name }; $enum; [$($variants)*]; $trait; [$($variants)*]);
+    };
+    (@trait_option { into_dyn $name:ident }; $enum:tt; [$($variants:tt)*]; $trait:tt) => {
+        $crate::enum_dispatch!(@without_via { into_dyn $name }; $enum; [$($variants)*]; $trait; [$($variants)*]);
+    };
+    (@without_via $option:tt; $enum:tt; $variants:tt; $trait:tt; [$(#[$var_attr:meta])* $variant:ident $pattern:tt ($($via:tt)*) $($rest:tt)*]) => {};
+    // unit variant has no value to borrow mutably, so mutable trait object isn't generated for enums with unit variants
+    (@without_via { as_dyn_mut $name:ident }; $enum:tt; $variants:tt; $trait:tt; [$(#[$var_attr:meta])* $variant:ident $pattern:tt [$unit:ident] $($rest:tt)*]) => {};
+    (@without_via $option:tt; $enum:tt; $variants:tt; $trait:tt; [$(#[$var_attr:meta])* $variant:ident $pattern:tt $binding:tt, $($rest:tt)*]) => {
+        $crate::enum_dispatch!(@without_via $option; $enum; $variants; $trait; [$($rest)*]);
+    };
+    (@without_via $option:tt; $enum:tt; $variants:tt; $trait:tt; [$(#[$var_attr:meta])* $variant:ident $pattern:tt $binding:tt; $options:tt]) => {
+        $crate::enum_dispatch!(@without_via $option; $enum; $variants; $trait; []);
+    };
+    (@without_via { as_dyn_mut $name:ident }; { $enum_vis:vis $enum_name:ident [$($lts:tt)*] [$($params:tt)*] [$($args:tt)*] [$($where_clause:tt)*] }; [$($(#[$var_attr:meta])* $variant:ident $pattern:tt $binding:tt),+; { [$($fallback:tt)*] $associated:tt $inline:tt }]; { $train_name:ident [$($trait_lts:tt)*] [$($trait_params:tt)*] [$($trait_args:tt)*] [$($trait_where:tt)*] [$(type $assoc_name:ident = $assoc_type:ty;)*] }; []) => {
+        impl<$($lts)* $($params)*> $enum_name<$($args)*> $($where_clause)* {
+            /// Inner value as a mutable trait object
+            #[allow(dead_code)]
+            $enum_vis fn $name<$($trait_lts)* $($trait_params)*>(&mut self) -> &mut dyn $train_name<$($trait_args)* $($assoc_name = $assoc_type,)*> $($trait_where)* {
+                match self {
+                    $(
+                        $(#[$var_attr])*
+                        $enum_name::$variant $pattern => $binding,
+                    )+
+                    $($fallback)*
+                }
+            }
+        }
+    };
+    // like `into_box`, boxed trait object lives as long as the enum
+    (@without_via { into_dyn $name:ident }; { $enum_vis:vis $enum_name:ident [$($lts:tt)*] [$($params:tt)*] [$($args:tt)*] [$($where_clause:tt)*] }; [$($(#[$var_attr:meta])* $variant:ident $pattern:tt $binding:tt),+; { [$($fallback:tt)*] $associated:tt $inline:tt }]; { $train_name:ident [$($trait_lts:tt)*] [$($trait_params:tt)*] [$($trait_args:tt)*] [$(where $($trait_where:tt)*)?] [$(type $assoc_name:ident = $assoc_type:ty;)*] }; []) => {
+        impl<$($lts)* $($params)*> $enum_name<$($args)*> $($where_clause)* {
+            /// Inner value as an owned trait object
+            #[allow(dead_code)]
+            $enum_vis fn $name<'__dispatch, $($trait_lts)* $($trait_params)*>(self) -> Box<dyn $train_name<$($trait_args)* $($assoc_name = $assoc_type,)*> + '__dispatch> where $($($trait_where)*)? Self: '__dispatch {
+                match self {
+                    $(
+                        $(#[$var_attr])*
+                        $enum_name::$variant $pattern => $crate::enum_dispatch!(@boxed_value $binding),
+                    )+
+                    $($fallback)*
+                }
+            }
+        }
+    };
+    // boxed trait object lives as long as the enum, so variants don't have to be `'static`
+    (@trait_option { into_box }; { $enum_vis:vis $enum_name:ident [$($lts:tt)*] [$($params:tt)*] [$($args:tt)*] [$(where $($where_clause:tt)*)?] }; [$($(#[$var_attr:meta])* $variant:ident $pattern:tt $binding:tt),+; { [$($fallback:tt)*] $associated:tt $inline:tt }]; { $train_name:ident [$($trait_lts:tt)*] [$($trait_params:tt)*] [$($trait_args:tt)*] [$(where $($trait_where:tt)*)?] [$(type $assoc_name:ident = $assoc_type:ty;)*] }) => {
+        impl<'__dispatch, $($trait_lts)* $($lts)* $($trait_params)* $($params)*> From<$enum_name<$($args)*>> for Box<dyn $train_name<$($trait_args)* $($assoc_name = $assoc_type,)*> + '__dispatch> where $($($trait_where)*)? $enum_name<$($args)*>: '__dispatch, $($($where_clause)*)? {
+            fn from(value: $enum_name<$($args)*>) -> Self {
+                match value {
+                    $(
+                        $(#[$var_attr])*
+                        $enum_name::$variant $pattern => $crate::enum_dispatch!(@boxed_value $binding),
+                    )+
+                    $($fallback)*
+                }
+            }
+        }
+    };
+    (@boxed_value [$unit:ident]) => {
+        Box::new($unit)
+    };
+    (@boxed_value $value:tt) => {
+        Box::new($value)
+    };
+    // also used to borrow the variant value for forwarded std traits
+    (@dyn_value [$unit:ident]) => {
+        &$unit
+    };
+    (@dyn_value $value:tt) => {
+        $value
+    };
+
+    (@conversions { $kind:tt { $fallback:tt $associated:tt $inline:tt $forward:tt $generate:tt $visitor:tt $match_macro:tt $derives:tt } $(#[$enum_attr:meta])* $enum_vis:vis enum $enum_name:ident [$($lts:tt)*] [$($params:tt)*] $args:tt $where_clause:tt; $traits:tt; $assoc:tt }; { $attrs:tt $cfgs:tt $variant:ident $fields:tt $pattern:tt $binding:tt $is_variant:tt $newtype:tt $dispatched:tt $options:tt }) => {
+        $crate::enum_dispatch!(@if_generated dispatch $generate {
+            $crate::enum_dispatch!(@implemented $cfgs; $dispatched; $enum_name [$($lts)* $($params)*] $args $where_clause; $traits);
+        });
+        $crate::enum_dispatch!(@variant $enum_vis $enum_name [$($lts)* $($params)*] $args $where_clause; $generate; $cfgs; $variant; $is_variant; $newtype; $options);
+    };
+
+    // call of a dummy function bounded by the trait reports missing implementation on the variant type instead of generated methods
+    // variant type keeps its span here, so unknown type is reported once at the variant too
+    // supertraits are in the same bound, so a missing one is reported at the variant even when the trait is implemented
+    // `Self` in `where` clause of the trait is the enum, as in the generated impl
+    (@implemented $cfgs:tt; []; $enum_name:ident $generics:tt $args:tt $where_clause:tt; $traits:tt) => {};
+    // method of a single trait is found on the variant value through `Deref`, so the value is checked the same way:
+    // method of a helper trait implemented for every type implementing the dispatched trait is looked up on it
+    (@implemented $cfgs:tt; $dispatched:tt; $enum_name:ident $generics:tt $args:tt $where_clause:tt; [$trait:tt]) => {
+        $crate::enum_dispatch!(@dereferenced_trait $cfgs; $dispatched; $enum_name $generics $args $where_clause; $trait);
+    };
+    (@implemented $cfgs:tt; $dispatched:tt; $enum_name:ident $generics:tt $args:tt $where_clause:tt; [$($trait:tt)+]) => {
+        $(
+            $crate::enum_dispatch!(@implemented_trait $cfgs; $dispatched; $enum_name $generics $args $where_clause; $trait);
+        )+
+    };
+    // arguments bound with `with trait` may refer to parameters of the enum
+    (@implemented_trait [$(#[$var_attr:meta])*]; [$variant_type:ty]; $enum_name:ident [$($generics:tt)*] [$($args:tt)*] [$(where $($where_clause:tt)*)?]; { $train_name:ident [] [] [$($trait_args:tt)*] [$(where $($trait_where:tt)*)?] $impl_attrs:tt [$(: $($supertraits:tt)*)?] [$($import:tt)*]; $methods:tt; $trait_assoc:tt; $trait_options:tt }) => {
+        $(#[$var_attr])*
+        const _: () = {
+            $($import)*
+
+            trait Implemented<$($generics)*> $(where $($trait_where)*)? {
+                fn implemented<Variant: $train_name<$($trait_args)*> $(+ $($supertraits)*)?>() {}
+
+                fn variant();
+            }
+
+            impl<$($generics)*> Implemented<$($args)*> for $enum_name<$($args)*> where $($($trait_where)*)? $($($where_clause)*)? {
+                fn variant() {
+                    <Self as Implemented<$($args)*>>::implemented::<$variant_type>();
+                }
+            }
+        };
+    };
+    (@implemented_trait [$(#[$var_attr:meta])*]; [$variant_type:ty]; $enum_name:ident [$($generics:tt)*] [$($args:tt)*] [$(where $($where_clause:tt)*)?]; { $train_name:ident [$($trait_lts:tt)*] [$($trait_params:tt)*] [$($trait_args:tt)*] [$(where $($trait_where:tt)*)?] $impl_attrs:tt [$(: $($supertraits:tt)*)?] [$($import:tt)*]; $methods:tt; $trait_assoc:tt; $trait_options:tt }) => {
+        $(#[$var_attr])*
+        const _: () = {
+            $($import)*
+
+            trait Implemented<$($trait_lts)* $($trait_params)*> $(where $($trait_where)*)? {
+                fn implemented<Variant: $train_name<$($trait_args)*> $(+ $($supertraits)*)?>() {}
+
+                fn variant();
+            }
+
+            impl<$($trait_lts)* $($generics)* $($trait_params)*> Implemented<$($trait_args)*> for $enum_name<$($args)*> where $($($trait_where)*)? $($($where_clause)*)? {
+                fn variant() {
+                    <Self as Implemented<$($trait_args)*>>::implemented::<$variant_type>();
+                }
+            }
+        };
+    };
+
+    // `Bound` carries parameters of the trait, so they are inferred from the argument of the lookup
+    (@dereferenced_trait [$(#[$var_attr:meta])*]; [$variant_type:ty]; $enum_name:ident [$($generics:tt)*] [$($args:tt)*] [$(where $($where_clause:tt)*)?]; { $train_name:ident [] [] [$($trait_args:tt)*] [$(where $($trait_where:tt)*)?] $impl_attrs:tt [$(: $($supertraits:tt)*)?] [$($import:tt)*]; $methods:tt; $trait_assoc:tt; $trait_options:tt }) => {
+        $(#[$var_attr])*
+        const _: () = {
+            $($import)*
+
+            trait Bound<$($generics)*> {}
+
+            trait Dispatched<Args: ?Sized> {
+                fn implemented(&self, _: ::core::marker::PhantomData<Args>) {}
+            }
+
+            impl<$($generics)* Variant: ?Sized + $train_name<$($trait_args)*> $(+ $($supertraits)*)?> Dispatched<dyn Bound<$($args)*>> for Variant $(where $($trait_where)*)? {}
+
+            trait Implemented<$($generics)*> $(where $($trait_where)*)? {
+                fn variant();
+            }
+
+            impl<$($generics)*> Implemented<$($args)*> for $enum_name<$($args)*> where $($($trait_where)*)? $($($where_clause)*)? {
+                fn variant() {
+                    let _ = |variant: &$variant_type| variant.implemented(::core::marker::PhantomData::<dyn Bound<$($args)*>>);
+                }
+            }
+        };
+    };
+    (@dereferenced_trait [$(#[$var_attr:meta])*]; [$variant_type:ty]; $enum_name:ident [$($generics:tt)*] [$($args:tt)*] [$(where $($where_clause:tt)*)?]; { $train_name:ident [$($trait_lts:tt)*] [$($trait_params:tt)*] [$($trait_args:tt)*] [$(where $($trait_where:tt)*)?] $impl_attrs:tt [$(: $($supertraits:tt)*)?] [$($import:tt)*]; $methods:tt; $trait_assoc:tt; $trait_options:tt }) => {
+        $(#[$var_attr])*
+        const _: () = {
+            $($import)*
+
+            trait Bound<$($trait_lts)* $($trait_params)*> {}
+
+            trait Dispatched<Args: ?Sized> {
+                fn implemented(&self, _: ::core::marker::PhantomData<Args>) {}
+            }
+
+            impl<$($trait_lts)* $($trait_params)* Variant: ?Sized + $train_name<$($trait_args)*> $(+ $($supertraits)*)?> Dispatched<dyn Bound<$($trait_args)*>> for Variant $(where $($trait_where)*)? {}
+
+            trait Implemented<$($trait_lts)* $($trait_params)*> $(where $($trait_where)*)? {
+                fn variant();
+            }
+
+            impl<$($trait_lts)* $($generics)* $($trait_params)*> Implemented<$($trait_args)*> for $enum_name<$($args)*> where $($($trait_where)*)? $($($where_clause)*)? {
+                fn variant() {
+                    let _ = |variant: &$variant_type| variant.implemented(::core::marker::PhantomData::<dyn Bound<$($trait_args)*>>);
+                }
+            }
+        };
+    };
+
+    (@variant $enum_vis:vis $enum_name:ident $generics:tt $args:tt $where_clause:tt; $generate:tt; $cfgs:tt; $variant:ident; $is_variant:tt; $newtype:tt; [no_from $($options:tt)*]) => {
+        $crate::enum_dispatch!(@variant $enum_vis $enum_name $generics $args $where_clause; $generate; $cfgs; $variant; $is_variant; []; [$($options)*]);
+    };
+    // `TryFrom` can't be implemented for a bare type parameter, `no_try_from` in front of generated items turns off `try_from` and `as_ref`
+    (@variant $enum_vis:vis $enum_name:ident $generics:tt $args:tt $where_clause:tt; [$($generate:ident)*]; $cfgs:tt; $variant:ident; $is_variant:tt; $newtype:tt; [no_try_from $($options:tt)*]) => {
+        $crate::enum_dispatch!(@variant $enum_vis $enum_name $generics $args $where_clause; [no_try_from $($generate)*]; $cfgs; $variant; $is_variant; $newtype; [$($options)*]);
+    };
+    (@variant $enum_vis:vis $enum_name:ident $generics:tt $args:tt $where_clause:tt; $generate:tt; $cfgs:tt; $variant:ident; $is_variant:tt; $newtype:tt; [{ via $accessor:tt } $($options:tt)*]) => {
+        $crate::enum_dispatch!(@variant $enum_vis $enum_name $generics $args $where_clause; $generate; $cfgs; $variant; $is_variant; $newtype; [$($options)*]);
+    };
+    // variant type is already parsed as `ty` and can't be looked into for `Box<Type>`, so the source type is named,
+    // the value is converted into the variant type with `From`, e.g. boxed
+    (@variant $enum_vis:vis $enum_name:ident [$($generics:tt)*] [$($args:tt)*] [$($where_clause:tt)*]; $generate:tt; [$(#[$var_attr:meta])*]; $variant:ident; $is_variant:tt; $newtype:tt; [{ from [$from:ty] } $($options:tt)*]) => {
+        $crate::enum_dispatch!(@if_generated from $generate {
+            $(#[$var_attr])*
+            impl<$($generics)*> From<$from> for $enum_name<$($args)*> $($where_clause)* {
+                 fn from(value: $from) -> Self {
+                     $enum_name::$variant(From::from(value))
+                 }
+            }
+        });
+        $crate::enum_dispatch!(@variant $enum_vis $enum_name [$($generics)*] [$($args)*] [$($where_clause)*]; $generate; [$(#[$var_attr])*]; $variant; $is_variant; $newtype; [$($options)*]);
+    };
+    (@variant $enum_vis:vis $enum_name:ident $generics:tt $args:tt $where_clause:tt; $generate:tt; $cfgs:tt; $variant:ident; $is_variant:tt; $newtype:tt; [{ visit $method:ident } $($options:tt)*]) => {
+        $crate::enum_dispatch!(@variant $enum_vis $enum_name $generics $args $where_clause; $generate; $cfgs; $variant; $is_variant; $newtype; [$($options)*]);
+    };
+    (@variant $enum_vis:vis $enum_name:ident $generics:tt $args:tt $where_clause:tt; $generate:tt; $cfgs:tt; $variant:ident; $is_variant:tt; $newtype:tt; [default $($options:tt)*]) => {
+        $crate::enum_dispatch!(@variant $enum_vis $enum_name $generics $args $where_clause; $generate; $cfgs; $variant; $is_variant; $newtype; [$($options)*]);
+    };
+    // conversions are generated only for variants with a single field
+    (@variant $enum_vis:vis $enum_name:ident [$($generics:tt)*] [$($args:tt)*] [$($where_clause:tt)*]; $generate:tt; [$(#[$var_attr:meta])*]; $variant:ident; [$($is_variant:ident)?]; [$variant_type:ty]; []) => {
+        $crate::enum_dispatch!(@if_generated from $generate {
+            $(#[$var_attr])*
+            impl<$($generics)*> From<$variant_type> for $enum_name<$($args)*> $($where_clause)* {
+                 fn from(value: $variant_type) -> Self {
+                     $enum_name::$variant(value)
+                 }
+            }
+        });
+
+        $crate::enum_dispatch!(@if_generated try_from $generate {
+            $(#[$var_attr])*
+            impl<$($generics)*> TryFrom<$enum_name<$($args)*>> for $variant_type $($where_clause)* {
+                type Error = $enum_name<$($args)*>;
+
+                fn try_from(value: $enum_name<$($args)*>) -> Result<Self, Self::Error> {
+                    #[allow(unreachable_patterns)]
+                    match value {
+                        $enum_name::$variant(v) => Ok(v),
+                        other => Err(other),
+                    }
+                }
+            }
+        });
+
+        $crate::enum_dispatch!(@if_generated as_ref $generate {
+            $(#[$var_attr])*
+            impl<'__dispatch, $($generics)*> TryFrom<&'__dispatch $enum_name<$($args)*>> for &'__dispatch $variant_type $($where_clause)* {
+                type Error = &'__dispatch $enum_name<$($args)*>;
+
+                fn try_from(value: &'__dispatch $enum_name<$($args)*>) -> Result<Self, Self::Error> {
+                    #[allow(unreachable_patterns)]
+                    match value {
+                        $enum_name::$variant(v) => Ok(v),
+                        other => Err(other),
+                    }
+                }
+            }
+
+            $(#[$var_attr])*
+            impl<'__dispatch, $($generics)*> TryFrom<&'__dispatch mut $enum_name<$($args)*>> for &'__dispatch mut $variant_type $($where_clause)* {
+                type Error = &'__dispatch mut $enum_name<$($args)*>;
+
+                fn try_from(value: &'__dispatch mut $enum_name<$($args)*>) -> Result<Self, Self::Error> {
+                    #[allow(unreachable_patterns)]
+                    match value {
+                        $enum_name::$variant(v) => Ok(v),
+                        other => Err(other),
+                    }
+                }
+            }
+        });
+
+        $crate::enum_dispatch!(@variant $enum_vis $enum_name [$($generics)*] [$($args)*] [$($where_clause)*]; $generate; [$(#[$var_attr])*]; $variant; [$($is_variant)?]; []; []);
+    };
+    (@variant $enum_vis:vis $enum_name:ident [$($generics:tt)*] [$($args:tt)*] [$($where_clause:tt)*]; $generate:tt; [$(#[$var_attr:meta])*]; $variant:ident; [$($is_variant:ident)?]; []; []) => {
+        $(#[$var_attr])*
+        impl<$($generics)*> $enum_name<$($args)*> $($where_clause)* {
+            $(
+                #[allow(dead_code)]
+                $enum_vis fn $is_variant(&self) -> bool {
+                    matches!(self, $enum_name::$variant { .. })
+                }
+            )?
+        }
+    };
+
+    // items are emitted only if `what` is in the list from `#[dispatch(generate(...))]`
+    (@if_generated dispatch [dispatch $($rest:ident)*] { $($item:tt)* }) => {
+        $($item)*
+    };
+    (@if_generated from [from $($rest:ident)*] { $($item:tt)* }) => {
+        $($item)*
+    };
+    (@if_generated try_from [try_from $($rest:ident)*] { $($item:tt)* }) => {
+        $($item)*
+    };
+    (@if_generated as_ref [as_ref $($rest:ident)*] { $($item:tt)* }) => {
+        $($item)*
+    };
+    (@if_generated variant_names [variant_names $($rest:ident)*] { $($item:tt)* }) => {
+        $($item)*
+    };
+    (@if_generated discriminant_index [discriminant_index $($rest:ident)*] { $($item:tt)* }) => {
+        $($item)*
+    };
+    (@if_generated try_from [no_try_from $($rest:ident)*] $items:tt) => {};
+    (@if_generated as_ref [no_try_from $($rest:ident)*] $items:tt) => {};
+    (@if_generated $what:ident [$other:ident $($rest:ident)*] $items:tt) => {
+        $crate::enum_dispatch!(@if_generated $what [$($rest)*] $items);
+    };
+    (@if_generated $what:ident [] $items:tt) => {};
+
+    // items of `generate(...)` are split into the ones generated by default and opt-in ones,
+    // default items are kept if none of them is listed
+    (@generate_items $ctx:tt; [$($default:ident)*]; $opt_in:tt; [dispatch $($items:ident)*]) => {
+        $crate::enum_dispatch!(@generate_items $ctx; [$($default)* dispatch]; $opt_in; [$($items)*]);
+    };
+    (@generate_items $ctx:tt; [$($default:ident)*]; $opt_in:tt; [from $($items:ident)*]) => {
+        $crate::enum_dispatch!(@generate_items $ctx; [$($default)* from]; $opt_in; [$($items)*]);
+    };
+    (@generate_items $ctx:tt; $default:tt; [$($opt_in:ident)*]; [try_from $($items:ident)*]) => {
+        $crate::enum_dispatch!(@generate_items $ctx; $default; [$($opt_in)* try_from]; [$($items)*]);
+    };
+    (@generate_items $ctx:tt; $default:tt; [$($opt_in:ident)*]; [as_ref $($items:ident)*]) => {
+        $crate::enum_dispatch!(@generate_items $ctx; $default; [$($opt_in)* as_ref]; [$($items)*]);
+    };
+    (@generate_items $ctx:tt; $default:tt; [$($opt_in:ident)*]; [variant_names $($items:ident)*]) => {
+        $crate::enum_dispatch!(@generate_items $ctx; $default; [$($opt_in)* variant_names]; [$($items)*]);
+    };
+    (@generate_items $ctx:tt; $default:tt; [$($opt_in:ident)*]; [discriminant_index $($items:ident)*]) => {
+        $crate::enum_dispatch!(@generate_items $ctx; $default; [$($opt_in)* discriminant_index]; [$($items)*]);
+    };
+    (@generate_items $ctx:tt; $default:tt; $opt_in:tt; [$item:ident $($items:ident)*]) => {
+        compile_error!(concat!("unknown generated item `", stringify!($item), "`, expected `dispatch`, `from`, `try_from`, `as_ref`, `variant_names` or `discriminant_index`"));
+    };
+    (@generate_items $ctx:tt; []; [$($opt_in:ident)*]; []) => {
+        $crate::enum_dispatch!(@generate_items $ctx; [dispatch from]; [$($opt_in)*]; []);
+    };
+    (@generate_items { $traits:tt; { $fallback:tt $associated:tt $inline:tt $forward:tt $visitor:tt $match_macro:tt $derives:tt }; $attrs:tt; $rest:tt }; [$($default:ident)+]; [$($opt_in:ident)*]; []) => {
+        $crate::enum_dispatch!(@enum_attrs $traits; { $fallback $associated $inline $forward [$($default)+ $($opt_in)*] $visitor $match_macro $derives }; $attrs; $rest);
+    };
+
+    // `#[dispatch(via = accessor)]` replaces the binding with accessor applied to it, e.g. `|v| &**v` for `Arc<Type>`
+    // type reached by accessor is unknown, so missing implementation is reported at the generated methods
+    (@accessors $head:tt; $done:tt; [{ $attrs:tt $cfgs:tt $variant:ident $fields:tt $pattern:tt $binding:tt $is_variant:tt $newtype:tt $dispatched:tt $options:tt } $($records:tt)*]) => {
+        $crate::enum_dispatch!(@accessor $head; $done; { $attrs $cfgs $variant $fields $pattern $binding $is_variant $newtype $dispatched $options }; $options; [$($records)*]);
+    };
+    (@accessors $head:tt; $done:tt; []) => {
+        $crate::enum_dispatch!(@generate $head; $done);
+    };
+    (@accessor $head:tt; $done:tt; { $attrs:tt $cfgs:tt $variant:ident $fields:tt $pattern:tt [$unit:ident] $($record:tt)* }; [{ via $accessor:tt } $($options:tt)*]; $records:tt) => {
+        compile_error!(concat!("unit variant `", stringify!($variant), "` has no value to apply `via` accessor to"));
+    };
+    (@accessor $head:tt; [$($done:tt)*]; { $attrs:tt $cfgs:tt $variant:ident $fields:tt $pattern:tt $binding:ident $is_variant:tt $newtype:tt $dispatched:tt $options:tt }; [{ via $accessor:tt } $($rest:tt)*]; $records:tt) => {
+        $crate::enum_dispatch!(@accessors $head; [$($done)* { $attrs $cfgs $variant $fields $pattern ($crate::__via($binding, $accessor)) $is_variant $newtype [] $options }]; $records);
+    };
+    (@accessor $head:tt; $done:tt; $record:tt; [$option:tt $($options:tt)*]; $records:tt) => {
+        $crate::enum_dispatch!(@accessor $head; $done; $record; [$($options)*]; $records);
+    };
+    (@accessor $head:tt; [$($done:tt)*]; $record:tt; []; $records:tt) => {
+        $crate::enum_dispatch!(@accessors $head; [$($done)* $record]; $records);
+    };
+
+    // variants are munched one by one, `fields` are collected token by token
+    (@variants $head:tt; [$($records:tt)*]; { }) => {
+        $crate::enum_dispatch!(@accessors $head; []; [$($records)*]);
+    };
+    (@variants $head:tt; $records:tt; { $($variants:tt)+ }) => {
+        $crate::enum_dispatch!(@attrs $head; $records; []; []; []; { $($variants)+ });
+    };
+
+    // markers like `#[dispatch(no_from)]` are not real attributes, so they are turned into options
+    (@attrs $head:tt; $records:tt; $attrs:tt; $cfgs:tt; [$($options:tt)*]; { #[dispatch(no_from)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@attrs $head; $records; $attrs; $cfgs; [$($options)* no_from]; { $($rest)* });
+    };
+    (@attrs $head:tt; $records:tt; $attrs:tt; $cfgs:tt; [$($options:tt)*]; { #[dispatch(no_try_from)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@attrs $head; $records; $attrs; $cfgs; [$($options)* no_try_from]; { $($rest)* });
+    };
+    (@attrs $head:tt; $records:tt; $attrs:tt; $cfgs:tt; [$($options:tt)*]; { #[dispatch(default)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@attrs $head; $records; $attrs; $cfgs; [$($options)* default]; { $($rest)* });
+    };
+    (@attrs $head:tt; $records:tt; $attrs:tt; $cfgs:tt; [$($options:tt)*]; { #[dispatch(via = $accessor:expr)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@attrs $head; $records; $attrs; $cfgs; [$($options)* { via ($accessor) }]; { $($rest)* });
+    };
+    (@attrs $head:tt; $records:tt; $attrs:tt; $cfgs:tt; [$($options:tt)*]; { #[dispatch(from = $from:ty)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@attrs $head; $records; $attrs; $cfgs; [$($options)* { from [$from] }]; { $($rest)* });
+    };
+    (@attrs $head:tt; $records:tt; $attrs:tt; $cfgs:tt; [$($options:tt)*]; { #[dispatch(visit = $method:ident)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@attrs $head; $records; $attrs; $cfgs; [$($options)* { visit $method }]; { $($rest)* });
+    };
+    (@attrs $head:tt; $records:tt; $attrs:tt; $cfgs:tt; $options:tt; { #[dispatch($($option:tt)*)] $($rest:tt)* }) => {
+        compile_error!(concat!("unknown dispatch option `", stringify!($($option)*), "`"));
+    };
+    (@attrs $head:tt; $records:tt; [$($attrs:tt)*]; [$($cfgs:tt)*]; $options:tt; { #[cfg $($cfg:tt)*] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@attrs $head; $records; [$($attrs)* #[cfg $($cfg)*]]; [$($cfgs)* #[cfg $($cfg)*]]; $options; { $($rest)* });
+    };
+    (@attrs $head:tt; $records:tt; [$($attrs:tt)*]; $cfgs:tt; $options:tt; { #[$($attr:tt)*] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@attrs $head; $records; [$($attrs)* #[$($attr)*]]; $cfgs; $options; { $($rest)* });
+    };
+    // explicit discriminant goes after the predicate, so `as` isn't parsed as a cast
+    (@attrs $head:tt; $records:tt; $attrs:tt; $cfgs:tt; $options:tt; { $variant:ident($($fields:tt)*) $(as $is_variant:ident)? $(= $discriminant:expr)? $(, $($rest:tt)*)? }) => {
+        $crate::enum_dispatch!(@fields $head; $records; { $attrs $cfgs $variant [$($is_variant)?] $options [$(= $discriminant)?] }; []; []; []; { $($fields)* }; { $($($rest)*)? });
+    };
+    (@attrs $head:tt; [$($records:tt)*]; $attrs:tt; $cfgs:tt; $options:tt; { $variant:ident $(as $is_variant:ident)? $(= $discriminant:expr)? $(, $($rest:tt)*)? }) => {
+        $crate::enum_dispatch!(@variants $head; [$($records)* { $attrs $cfgs $variant [$(= $discriminant)?] {} [$variant] [$($is_variant)?] [] [$variant] $options }]; { $($($rest)*)? });
+    };
+
+    // field marked with `#[dispatch]` implements the trait
+    (@fields $head:tt; $records:tt; { $attrs:tt $cfgs:tt $variant:ident $is_variant:tt $options:tt $discriminant:tt }; $types:tt; $pattern:tt; [$binding:ident $dispatched:tt]; { #[dispatch] $($fields:tt)* }; $rest:tt) => {
+        compile_error!(concat!("only one field of variant `", stringify!($variant), "` can be marked with `#[dispatch]`"));
+    };
+    (@fields $head:tt; $records:tt; $variant:tt; [$($types:tt)*]; [$($pattern:tt)*]; []; { #[dispatch] $field:ty $(, $($fields:tt)*)? }; $rest:tt) => {
+        $crate::enum_dispatch!(@fields $head; $records; $variant; [$($types)* $field,]; [$($pattern)* v,]; [v [$field]]; { $($($fields)*)? }; $rest);
+    };
+    (@fields $head:tt; $records:tt; $variant:tt; [$($types:tt)*]; [$($pattern:tt)*]; $binding:tt; { $field:ty $(, $($fields:tt)*)? }; $rest:tt) => {
+        $crate::enum_dispatch!(@fields $head; $records; $variant; [$($types)* $field,]; [$($pattern)* _,]; $binding; { $($($fields)*)? }; $rest);
+    };
+    // single field doesn't have to be marked
+    (@fields $head:tt; [$($records:tt)*]; { $attrs:tt $cfgs:tt $variant:ident $is_variant:tt $options:tt [$($discriminant:tt)*] }; [$field:ty,]; [_,]; []; { }; $rest:tt) => {
+        $crate::enum_dispatch!(@variants $head; [$($records)* { $attrs $cfgs $variant [($field) $($discriminant)*] (v) v $is_variant [$field] [$field] $options }]; $rest);
+    };
+    (@fields $head:tt; [$($records:tt)*]; { $attrs:tt $cfgs:tt $variant:ident $is_variant:tt $options:tt [$($discriminant:tt)*] }; [$($types:tt)*]; [$($pattern:tt)*]; [$binding:ident $dispatched:tt]; { }; $rest:tt) => {
+        $crate::enum_dispatch!(@variants $head; [$($records)* { $attrs $cfgs $variant [($($types)*) $($discriminant)*] ($($pattern)*) $binding $is_variant [] $dispatched $options }]; $rest);
+    };
+    (@fields $head:tt; $records:tt; { $attrs:tt $cfgs:tt $variant:ident $is_variant:tt $options:tt $discriminant:tt }; $types:tt; $pattern:tt; []; { }; $rest:tt) => {
+        compile_error!(concat!("field of variant `", stringify!($variant), "` implementing the trait should be marked with `#[dispatch]`"));
+    };
+
+    (
+        $(#[$($trait_attr:tt)*])*
+        $trait_vis:vis trait $($rest:tt)*
+    ) => {
+        $crate::enum_dispatch!(@trait_attrs []; []; []; []; { $(#[$($trait_attr)*])* $trait_vis trait $($rest)* });
+    };
+    (
+        $(#[$($trait_attr:tt)*])*
+        use trait $($rest:tt)*
+    ) => {
+        $crate::enum_dispatch!(@trait_attrs []; []; []; []; { $(#[$($trait_attr)*])* use trait $($rest)* });
+    };
+
+    // `#[dispatch(...)]` options of the trait are collected into `options`, other attributes are kept on the trait
+    // `#[dispatch(as_dyn)]` generates `as_dyn`, `as_dyn_mut` and `into_dyn` accessors returning the variant as a trait object,
+    // names can be changed with `#[dispatch(as_dyn = name)]`, `#[dispatch(as_dyn_mut = name)]` and `#[dispatch(into_dyn = name)]`
+    // to have accessors for several traits
+    (@trait_attrs $traits:tt; [$($options:tt)*]; $impl_attrs:tt; $attrs:tt; { #[dispatch(as_dyn)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_attrs $traits; [$($options)* { as_dyn as_dyn } { as_dyn_mut as_dyn_mut } { into_dyn into_dyn }]; $impl_attrs; $attrs; { $($rest)* });
+    };
+    (@trait_attrs $traits:tt; [$($options:tt)*]; $impl_attrs:tt; $attrs:tt; { #[dispatch(as_dyn = $name:ident)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_attrs $traits; [$($options)* { as_dyn $name }]; $impl_attrs; $attrs; { $($rest)* });
+    };
+    (@trait_attrs $traits:tt; [$($options:tt)*]; $impl_attrs:tt; $attrs:tt; { #[dispatch(as_dyn_mut = $name:ident)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_attrs $traits; [$($options)* { as_dyn_mut $name }]; $impl_attrs; $attrs; { $($rest)* });
+    };
+    (@trait_attrs $traits:tt; [$($options:tt)*]; $impl_attrs:tt; $attrs:tt; { #[dispatch(into_dyn = $name:ident)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_attrs $traits; [$($options)* { into_dyn $name }]; $impl_attrs; $attrs; { $($rest)* });
+    };
+    // `#[dispatch(into_box)]` generates conversion of the enum into `Box<dyn Trait>`
+    (@trait_attrs $traits:tt; [$($options:tt)*]; $impl_attrs:tt; $attrs:tt; { #[dispatch(into_box)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_attrs $traits; [$($options)* { into_box }]; $impl_attrs; $attrs; { $($rest)* });
+    };
+    // `#[dispatch(async_trait)]` rewrites `async fn` the way `#[async_trait]` does, it is kept first, so `@trait_body` can find it
+    // markers to bound the futures and `Self` with are `Send` and `Sync`, none for `#[dispatch(async_trait(?Send))]`
+    (@trait_attrs $traits:tt; [$($options:tt)*]; $impl_attrs:tt; $attrs:tt; { #[dispatch(async_trait)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_attrs $traits; [{ async_trait { Send Sync } } $($options)*]; $impl_attrs; $attrs; { $($rest)* });
+    };
+    (@trait_attrs $traits:tt; [$($options:tt)*]; $impl_attrs:tt; $attrs:tt; { #[dispatch(async_trait(?Send))] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_attrs $traits; [{ async_trait { } } $($options)*]; $impl_attrs; $attrs; { $($rest)* });
+    };
+    // `#[dispatch(attr(...))]` is applied to the generated impl instead of the trait
+    (@trait_attrs $traits:tt; $options:tt; [$($impl_attrs:tt)*]; $attrs:tt; { #[dispatch(attr($($attr:tt)*))] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_attrs $traits; $options; [$($impl_attrs)* #[$($attr)*]]; $attrs; { $($rest)* });
+    };
+    (@trait_attrs $traits:tt; $options:tt; $impl_attrs:tt; $attrs:tt; { #[dispatch($($option:tt)*)] $($rest:tt)* }) => {
+        compile_error!(concat!("unknown dispatch option `", stringify!($($option)*), "`"));
+    };
+    (@trait_attrs $traits:tt; $options:tt; $impl_attrs:tt; [$($attrs:tt)*]; { #[$($attr:tt)*] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_attrs $traits; $options; $impl_attrs; [$($attrs)* #[$($attr)*]]; { $($rest)* });
+    };
+    (@trait_attrs $traits:tt; $options:tt; $impl_attrs:tt; [$($attrs:tt)*]; { $trait_vis:vis trait $train_name:ident $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_generics { $traits; $options; $impl_attrs; $($attrs)* $trait_vis trait $train_name }; { $($rest)* });
+    };
+    // trait declared with `use trait path::Trait` is defined outside of the macro, its methods are listed for dispatch only
+    (@trait_attrs $traits:tt; $options:tt; $impl_attrs:tt; $attrs:tt; { use trait $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_path $traits; $options; $impl_attrs; []; { $($rest)* });
+    };
+    (@trait_path $traits:tt; $options:tt; $impl_attrs:tt; []; { :: $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_path $traits; $options; $impl_attrs; [::]; { $($rest)* });
+    };
+    (@trait_path $traits:tt; $options:tt; $impl_attrs:tt; [$($path:tt)*]; { $segment:ident :: $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_path $traits; $options; $impl_attrs; [$($path)* $segment ::]; { $($rest)* });
+    };
+    (@trait_path $traits:tt; $options:tt; $impl_attrs:tt; [$($path:tt)*]; { $train_name:ident $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_generics { $traits; $options; $impl_attrs; use trait [$($path)* $train_name] $train_name }; { $($rest)* });
+    };
+
+    // generic parameters are collected into lifetimes and other `params`, names of parameters are collected for `args`
+    // lifetimes are kept separately because they have to be declared first in the `impl` of generic trait for generic enum
+    (@trait_generics $ctx:tt; { < $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generic_param trait_header $ctx; [] [] []; { $($rest)* });
+    };
+    (@trait_generics $ctx:tt; { $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_header $ctx; [] [] []; []; { $($rest)* });
+    };
+
+    // supertraits are collected token by token until the body of the trait
+    (@trait_header $ctx:tt; $lts:tt $params:tt $args:tt; $header:tt; { { $($any:tt)* } $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_body $ctx; $lts $params $args; $header; []; { { $($any)* } $($rest)* });
+    };
+    (@trait_header $ctx:tt; $lts:tt $params:tt $args:tt; $header:tt; { where $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_where $ctx; $lts $params $args; $header; [where]; { $($rest)* });
+    };
+    (@trait_header $ctx:tt; $lts:tt $params:tt $args:tt; [$($header:tt)*]; { $next:tt $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_header $ctx; $lts $params $args; [$($header)* $next]; { $($rest)* });
+    };
+    // `where` clause of the trait is repeated on every `impl`, so it always ends with a comma
+    (@trait_where $ctx:tt; $lts:tt $params:tt $args:tt; $header:tt; [$($where_clause:tt)*]; { , { $($any:tt)* } $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_body $ctx; $lts $params $args; $header; [$($where_clause)* ,]; { { $($any)* } $($rest)* });
+    };
+    (@trait_where $ctx:tt; $lts:tt $params:tt $args:tt; $header:tt; [$($where_clause:tt)*]; { { $($any:tt)* } $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_body $ctx; $lts $params $args; $header; [$($where_clause)* ,]; { { $($any)* } $($rest)* });
+    };
+    (@trait_where $ctx:tt; $lts:tt $params:tt $args:tt; $header:tt; [$($where_clause:tt)*]; { $next:tt $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_where $ctx; $lts $params $args; $header; [$($where_clause)* $next]; { $($rest)* });
+    };
+    // methods start with a marker, so the trait and the generated impl are munched with the same rewrite of `async fn`
+    (@trait_body { $traits:tt; [{ async_trait $send:tt } $($options:tt)*]; $($trait:tt)* }; $lts:tt $params:tt $args:tt; $header:tt; $where_clause:tt; { { $($any:tt)* } $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_body { $traits; [$($options)*]; $($trait)* }; $lts $params $args; $header; $where_clause; { { @async_trait $send $($any)* } $($rest)* });
+    };
+    (@trait_body { $traits:tt; $options:tt; $impl_attrs:tt; $(#[$trait_attr:meta])* $trait_vis:vis trait $train_name:ident }; [$($lts:tt)*] [$($params:tt)*] $args:tt; [$($header:tt)*]; [$($where_clause:tt)*]; { { $($any:tt)* } $($rest:tt)* }) => {
+        $(#[$trait_attr])*
+        $trait_vis trait $train_name<$($lts)* $($params)*> $($header)* $($where_clause)* {
+            $crate::__munch_methods!({ $($any)* }; [trait]);
+        }
+
+        $crate::enum_dispatch!(@after_trait $traits { $train_name [$($lts)*] [$($params)*] $args [$($where_clause)*] $impl_attrs [$($header)*] []; { $($any)* }; []; $options }; { $($rest)* });
+    };
+    // generated items of the trait are emitted where its path is imported
+    (@trait_body { $traits:tt; $options:tt; $impl_attrs:tt; use trait [$($path:tt)*] $train_name:ident }; [$($lts:tt)*] [$($params:tt)*] $args:tt; [$($header:tt)*]; [$($where_clause:tt)*]; { { $($any:tt)* } $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@after_trait $traits { $train_name [$($lts)*] [$($params)*] $args [$($where_clause)*] $impl_attrs [$($header)*] [use $($path)*;]; { $($any)* }; []; $options }; { $($rest)* });
+    };
+
+    // trait can be followed by associated types bound for it, next trait or the enum
+    (@after_trait $traits:tt { $train_name:ident $lts:tt $params:tt $args:tt $trait_where:tt $impl_attrs:tt $supertraits:tt $import:tt; $methods:tt; [$($assoc:tt)*]; $options:tt }; { with type $assoc_name:ident = $assoc_type:ty; $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@after_trait $traits { $train_name $lts $params $args $trait_where $impl_attrs $supertraits $import; $methods; [$($assoc)* type $assoc_name = $assoc_type;]; $options }; { $($rest)* });
+    };
+    // `with trait` binds parameters of the trait to parameters of the enum with the same name or to concrete types,
+    // which are declared as type aliases where the impl is emitted
+    (@after_trait $traits:tt { $train_name:ident $lts:tt $params:tt $args:tt $trait_where:tt $impl_attrs:tt $supertraits:tt [$($import:tt)*]; $methods:tt; $assoc:tt; $options:tt }; { with trait $name:ident<$($arg_name:ident $(= $arg_type:ty)?),+ $(,)?>; $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@after_trait $traits { $name [] [] [$($arg_name,)+] $trait_where $impl_attrs $supertraits [$($import)* $($(type $arg_name = $arg_type;)?)+]; $methods; $assoc; $options }; { $($rest)* });
+    };
+    (@after_trait [$($traits:tt)*] $trait:tt; { $(#[$($trait_attr:tt)*])* $trait_vis:vis trait $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_attrs [$($traits)* $trait]; []; []; []; { $(#[$($trait_attr)*])* $trait_vis trait $($rest)* });
+    };
+    (@after_trait [$($traits:tt)*] $trait:tt; { $(#[$($trait_attr:tt)*])* use trait $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@trait_attrs [$($traits)* $trait]; []; []; []; { $(#[$($trait_attr)*])* use trait $($rest)* });
+    };
+    (@after_trait [$($traits:tt)*] $trait:tt; { $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enums [$($traits)* $trait]; { $($rest)* });
+    };
+
+    // every enum after the traits gets its own dispatch with default options
+    (@enums $traits:tt; { $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum_attrs $traits; { [] [] [#[inline]] [] [dispatch from] [] [] [[] []] }; []; { $($rest)* });
+    };
+
+    // enum options are `{ [fallback arm] [type for associated functions] [default method attributes] [forwarded std traits] [generated items] [visitor trait] [match macro] [[derive checks] [derived traits]] }`
+    // `#[dispatch(fallback)]` adds a wildcard arm for variants that are not listed, e.g. of `existing` enum
+    (@enum_attrs $traits:tt; { $fallback:tt $associated:tt $inline:tt $forward:tt $generate:tt $visitor:tt $match_macro:tt $derives:tt }; $attrs:tt; { #[dispatch(fallback)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum_attrs $traits; { [#[allow(unreachable_patterns)] _ => unreachable!("variant is not dispatched")] $associated $inline $forward $generate $visitor $match_macro $derives }; $attrs; { $($rest)* });
+    };
+    // associated functions without `self` are forwarded to the type from `#[dispatch(associated = Type)]`
+    (@enum_attrs $traits:tt; { $fallback:tt $associated:tt $inline:tt $forward:tt $generate:tt $visitor:tt $match_macro:tt $derives:tt }; $attrs:tt; { #[dispatch(associated = $associated_type:ty)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum_attrs $traits; { $fallback [$associated_type] $inline $forward $generate $visitor $match_macro $derives }; $attrs; { $($rest)* });
+    };
+    // generated methods are `#[inline]` unless disabled with `#[dispatch(no_inline)]`
+    (@enum_attrs $traits:tt; { $fallback:tt $associated:tt $inline:tt $forward:tt $generate:tt $visitor:tt $match_macro:tt $derives:tt }; $attrs:tt; { #[dispatch(no_inline)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum_attrs $traits; { $fallback $associated [] $forward $generate $visitor $match_macro $derives }; $attrs; { $($rest)* });
+    };
+    // `#[dispatch(display)]` implements `Display` of the enum with `Display` of the variant value
+    (@enum_attrs $traits:tt; { $fallback:tt $associated:tt $inline:tt [$($forward:ident)*] $generate:tt $visitor:tt $match_macro:tt $derives:tt }; $attrs:tt; { #[dispatch(display)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum_attrs $traits; { $fallback $associated $inline [$($forward)* display] $generate $visitor $match_macro $derives }; $attrs; { $($rest)* });
+    };
+    // `#[dispatch(partial_eq)]` implements `PartialEq` of the enum comparing values of the same variant
+    (@enum_attrs $traits:tt; { $fallback:tt $associated:tt $inline:tt [$($forward:ident)*] $generate:tt $visitor:tt $match_macro:tt $derives:tt }; $attrs:tt; { #[dispatch(partial_eq)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum_attrs $traits; { $fallback $associated $inline [$($forward)* partial_eq] $generate $visitor $match_macro $derives }; $attrs; { $($rest)* });
+    };
+    // `#[dispatch(generate(...))]` limits items generated by default to the listed ones and adds listed opt-in items
+    (@enum_attrs $traits:tt; { $fallback:tt $associated:tt $inline:tt $forward:tt $generate:tt $visitor:tt $match_macro:tt $derives:tt }; $attrs:tt; { #[dispatch(generate($($item:ident),* $(,)?))] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generate_items { $traits; { $fallback $associated $inline $forward $visitor $match_macro $derives }; $attrs; { $($rest)* } }; []; []; [$($item)*]);
+    };
+    // `#[dispatch(visitor = Name)]` generates visitor trait with a method for every variant and `visit` method calling it
+    (@enum_attrs $traits:tt; { $fallback:tt $associated:tt $inline:tt $forward:tt $generate:tt $visitor:tt $match_macro:tt $derives:tt }; $attrs:tt; { #[dispatch(visitor = $name:ident)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum_attrs $traits; { $fallback $associated $inline $forward $generate [$name] $match_macro $derives }; $attrs; { $($rest)* });
+    };
+    // `#[dispatch(match_macro = name)]` generates `name!(value, |inner| body)` macro matching every variant
+    (@enum_attrs $traits:tt; { $fallback:tt $associated:tt $inline:tt $forward:tt $generate:tt $visitor:tt $match_macro:tt $derives:tt }; $attrs:tt; { #[dispatch(match_macro = $name:ident)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum_attrs $traits; { $fallback $associated $inline $forward $generate $visitor [$name] $derives }; $attrs; { $($rest)* });
+    };
+    // `#[dispatch(check_derives)]` checks that fields of every variant implement derived `Clone`, `Debug`, `PartialEq` and `Hash`,
+    // derived traits are collected from `#[derive(...)]` on the enum wherever the option is
+    (@enum_attrs $traits:tt; { $fallback:tt $associated:tt $inline:tt $forward:tt $generate:tt $visitor:tt $match_macro:tt [$check:tt $derived:tt] }; $attrs:tt; { #[dispatch(check_derives)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum_attrs $traits; { $fallback $associated $inline $forward $generate $visitor $match_macro [[check] $derived] }; $attrs; { $($rest)* });
+    };
+    (@enum_attrs $traits:tt; $dispatch:tt; $attrs:tt; { #[dispatch($($option:tt)*)] $($rest:tt)* }) => {
+        compile_error!(concat!("unknown dispatch option `", stringify!($($option)*), "`"));
+    };
+    (@enum_attrs $traits:tt; { $fallback:tt $associated:tt $inline:tt $forward:tt $generate:tt $visitor:tt $match_macro:tt [$check:tt [$($derived:tt)*]] }; [$($attrs:tt)*]; { #[derive($($derive:tt)*)] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum_attrs $traits; { $fallback $associated $inline $forward $generate $visitor $match_macro [$check [$($derived)* $($derive)*]] }; [$($attrs)* #[derive($($derive)*)]]; { $($rest)* });
+    };
+    (@enum_attrs $traits:tt; $dispatch:tt; [$($attrs:tt)*]; { #[$($attr:tt)*] $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum_attrs $traits; $dispatch; [$($attrs)* #[$($attr)*]]; { $($rest)* });
+    };
+    (@enum_attrs $traits:tt; $dispatch:tt; [$($attrs:tt)*]; { $enum_vis:vis existing enum $enum_name:ident $($enum_def:tt)* }) => {
+        $crate::enum_dispatch!(@enum_generics { [existing] $dispatch $($attrs)* $enum_vis enum $enum_name; $traits }; { $($enum_def)* });
+    };
+    (@enum_attrs $traits:tt; $dispatch:tt; [$($attrs:tt)*]; { $enum_vis:vis enum $enum_name:ident $($enum_def:tt)* }) => {
+        $crate::enum_dispatch!(@enum_generics { [] $dispatch $($attrs)* $enum_vis enum $enum_name; $traits }; { $($enum_def)* });
+    };
+
+    (@enum_generics $ctx:tt; { < $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generic_param enum_where $ctx; [] [] []; { $($rest)* });
+    };
+    (@enum_generics $ctx:tt; { $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum_where $ctx; [] [] []; []; { $($rest)* });
+    };
+
+    // after the closing `>` munching continues with `@$next`
+    (@generic_param $next:ident $ctx:tt; $lts:tt $params:tt $args:tt; { > $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@$next $ctx; $lts $params $args; []; { $($rest)* });
+    };
+    (@generic_param $next:ident $ctx:tt; $lts:tt $params:tt [$($args:tt)*]; { $lifetime:lifetime $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generics $next $ctx; $lts $params [$($args)* $lifetime,]; [lifetime $lifetime]; []; { $($rest)* });
+    };
+    (@generic_param $next:ident $ctx:tt; $lts:tt $params:tt [$($args:tt)*]; { const $param:ident $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generics $next $ctx; $lts $params [$($args)* $param,]; [param const $param]; []; { $($rest)* });
+    };
+    (@generic_param $next:ident $ctx:tt; $lts:tt $params:tt [$($args:tt)*]; { $param:ident $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generics $next $ctx; $lts $params [$($args)* $param,]; [param $param]; []; { $($rest)* });
+    };
+
+    // `depth` holds a `<` for every unclosed angle bracket inside of generic parameters
+    (@generics $next:ident $ctx:tt; $lts:tt $params:tt $args:tt; $param:tt; []; { > $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generic_push $next $ctx; $lts $params $args; $param; { > $($rest)* });
+    };
+    (@generics $next:ident $ctx:tt; $lts:tt $params:tt $args:tt; [$($param:tt)*]; [<]; { >> $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generic_push $next $ctx; $lts $params $args; [$($param)* >]; { > $($rest)* });
+    };
+    (@generics $next:ident $ctx:tt; $lts:tt $params:tt $args:tt; $param:tt; []; { , $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generic_push $next $ctx; $lts $params $args; $param; { , $($rest)* });
+    };
+    (@generics $next:ident $ctx:tt; $lts:tt $params:tt $args:tt; [$($param:tt)*]; [$($depth:tt)*]; { < $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generics $next $ctx; $lts $params $args; [$($param)* <]; [$($depth)* <]; { $($rest)* });
+    };
+    (@generics $next:ident $ctx:tt; $lts:tt $params:tt $args:tt; [$($param:tt)*]; [< $($depth:tt)*]; { > $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generics $next $ctx; $lts $params $args; [$($param)* >]; [$($depth)*]; { $($rest)* });
+    };
+    (@generics $next:ident $ctx:tt; $lts:tt $params:tt $args:tt; [$($param:tt)*]; [< < $($depth:tt)*]; { >> $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generics $next $ctx; $lts $params $args; [$($param)* >>]; [$($depth)*]; { $($rest)* });
+    };
+    (@generics $next:ident $ctx:tt; $lts:tt $params:tt $args:tt; [$($param:tt)*]; $depth:tt; { $token:tt $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generics $next $ctx; $lts $params $args; [$($param)* $token]; $depth; { $($rest)* });
+    };
+
+    (@generic_push $next:ident $ctx:tt; [$($lts:tt)*] $params:tt $args:tt; [lifetime $($param:tt)*]; { $delimiter:tt $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generic_param $next $ctx; [$($lts)* $($param)*,] $params $args; { $($rest)* } $delimiter);
+    };
+    (@generic_push $next:ident $ctx:tt; $lts:tt [$($params:tt)*] $args:tt; [param $($param:tt)*]; { $delimiter:tt $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@generic_param $next $ctx; $lts [$($params)* $($param)*,] $args; { $($rest)* } $delimiter);
+    };
+    (@generic_param $next:ident $ctx:tt; $lts:tt $params:tt $args:tt; { $($rest:tt)* } ,) => {
+        $crate::enum_dispatch!(@generic_param $next $ctx; $lts $params $args; { $($rest)* });
+    };
+    (@generic_param $next:ident $ctx:tt; $lts:tt $params:tt $args:tt; { $($rest:tt)* } >) => {
+        $crate::enum_dispatch!(@$next $ctx; $lts $params $args; []; { $($rest)* });
+    };
+
+    // `where` clause is collected token by token until the body of the enum
+    (@enum_where $ctx:tt; $lts:tt $params:tt $args:tt; $where_clause:tt; { { $($variants:tt)* } $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@after_enum { $ctx; $lts $params $args $where_clause; { $($variants)* } }; []; []; { $($rest)* });
+    };
+    // enum can be followed by associated types bound for it, `extra impl { ... }` emitted as is in an inherent impl of the enum
+    // and next enum dispatched with the same traits
+    (@after_enum $enum:tt; [$($assoc:tt)*]; $extra:tt; { with type $assoc_name:ident = $assoc_type:ty; $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@after_enum $enum; [$($assoc)* type $assoc_name = $assoc_type;]; $extra; { $($rest)* });
+    };
+    (@after_enum $enum:tt; $assoc:tt; []; { extra impl { $($extra:tt)* } $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@after_enum $enum; $assoc; [{ $($extra)* }]; { $($rest)* });
+    };
+    (@after_enum { { $kind:tt $dispatch:tt $(#[$enum_attr:meta])* $enum_vis:vis enum $enum_name:ident; $traits:tt }; $lts:tt $params:tt $args:tt $where_clause:tt; $variants:tt }; $assoc:tt; [$($extra:tt)?]; { $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum { $kind $dispatch $(#[$enum_attr])* $enum_vis enum $enum_name $lts $params $args $where_clause; $traits; $assoc }; $variants);
+        $(
+            $crate::enum_dispatch!(@extra_impl $enum_name $lts $params $args $where_clause; $extra);
+        )?
+        $crate::enum_dispatch!(@next_enum $traits; { $($rest)* });
+    };
+    (@next_enum $traits:tt; { }) => {};
+    (@next_enum $traits:tt; { $($rest:tt)+ }) => {
+        $crate::enum_dispatch!(@enums $traits; { $($rest)+ });
+    };
+    (@extra_impl $enum_name:ident [$($lts:tt)*] [$($params:tt)*] [$($args:tt)*] [$($where_clause:tt)*]; { $($extra:tt)* }) => {
+        impl<$($lts)* $($params)*> $enum_name<$($args)*> $($where_clause)* {
+            $($extra)*
+        }
+    };
+    (@enum_where $ctx:tt; $lts:tt $params:tt $args:tt; [$($where_clause:tt)*]; { $next:tt $($rest:tt)* }) => {
+        $crate::enum_dispatch!(@enum_where $ctx; $lts $params $args; [$($where_clause)* $next]; { $($rest)* });
+    };
+
+    // newtype variants with only doc comments and `cfg` attributes are normalized at once, other forms are munched one by one
+    (@enum $head:tt; { $($(#[doc $($doc:tt)*])* $(#[cfg $cfg:tt])* $variant:ident($variant_type:ty) $(as $is_variant:ident)?),+ $(,)? }) => {
+        $crate::enum_dispatch!(@generate $head; [$({ [$(#[doc $($doc)*])* $(#[cfg $cfg])*] [$(#[cfg $cfg])*] $variant [($variant_type)] (v) v [$($is_variant)?] [$variant_type] [$variant_type] [] })+]);
+    };
+    (@enum $head:tt; { $($variants:tt)* }) => {
+        $crate::enum_dispatch!(@variants $head; []; { $($variants)* });
+    };
+}
+
+/// Attribute macro alternative of [`enum_dispatch!`], enabled with the `proc-macro` feature,
+/// see [Proc-macro](crate#proc-macro)
+#[cfg(feature = "proc-macro")]
+pub mod attributes {
+    pub use declarative_enum_dispatch_macros::enum_dispatch;
+
+    #[doc(hidden)]
+    pub use declarative_enum_dispatch_macros::__dispatch;
 }
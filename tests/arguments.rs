@@ -0,0 +1,182 @@
+//! Arguments are passed to the variant in the same order, however many of them there are,
+//! receiver can be followed by a trailing comma without other arguments and arguments can be trait objects
+
+#![allow(clippy::too_many_arguments)]
+
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Record {
+        fn write<T: std::fmt::Display>(
+            &self,
+            a: u8,
+            b: &str,
+            c: T,
+            d: &mut Vec<u32>,
+            e: Option<char>,
+            f: (i32, i32),
+            g: impl Into<String>,
+            h: &[u16],
+            i: bool,
+            j: f64,
+        ) -> String;
+        fn into_record(
+            self,
+            a: u8,
+            b: u16,
+            c: u32,
+            d: u64,
+            e: i8,
+            f: i16,
+            g: i32,
+            h: i64,
+            i: char,
+            j: &str,
+        ) -> String;
+    }
+
+    pub enum AnyRecord {
+        Csv(Csv),
+    }
+);
+
+enum_dispatch!(
+    pub trait Named {
+        fn name(&self,) -> String;
+        fn rename(&mut self, suffix: char,);
+        fn into_name(self: Box<Self>,) -> String;
+    }
+
+    pub enum AnyNamed {
+        User(User),
+    }
+);
+
+pub struct Csv;
+pub struct User(String);
+
+impl Record for Csv {
+    fn write<T: std::fmt::Display>(
+        &self,
+        a: u8,
+        b: &str,
+        c: T,
+        d: &mut Vec<u32>,
+        e: Option<char>,
+        f: (i32, i32),
+        g: impl Into<String>,
+        h: &[u16],
+        i: bool,
+        j: f64,
+    ) -> String {
+        d.push(a as u32);
+        format!("{a},{b},{c},{:?},{e:?},{f:?},{},{h:?},{i},{j}", d, g.into())
+    }
+
+    fn into_record(
+        self,
+        a: u8,
+        b: u16,
+        c: u32,
+        d: u64,
+        e: i8,
+        f: i16,
+        g: i32,
+        h: i64,
+        i: char,
+        j: &str,
+    ) -> String {
+        format!("{a},{b},{c},{d},{e},{f},{g},{h},{i},{j}")
+    }
+}
+
+impl Named for User {
+    fn name(&self,) -> String {
+        self.0.clone()
+    }
+    fn rename(&mut self, suffix: char,) {
+        self.0.push(suffix);
+    }
+    fn into_name(self: Box<Self>,) -> String {
+        self.0
+    }
+}
+
+#[test]
+fn ten_arguments_keep_their_order() {
+    let record = AnyRecord::from(Csv);
+    let mut seen = vec![0];
+    assert_eq!(
+        record.write(
+            1,
+            "b",
+            'c',
+            &mut seen,
+            Some('e'),
+            (6, 7),
+            "g",
+            &[8, 9],
+            true,
+            1.5
+        ),
+        "1,b,c,[0, 1],Some('e'),(6, 7),g,[8, 9],true,1.5",
+    );
+    assert_eq!(
+        record.into_record(1, 2, 3, 4, -5, -6, 7, 8, '9', "10"),
+        "1,2,3,4,-5,-6,7,8,9,10"
+    );
+}
+
+#[test]
+fn trailing_commas_are_accepted() {
+    let mut user = AnyNamed::from(User("bob".to_string()));
+    user.rename('!');
+    assert_eq!(user.name(), "bob!");
+    assert_eq!(Box::new(user).into_name(), "bob!");
+}
+
+pub trait Canvas {
+    fn draw(&self, name: &str) -> String;
+}
+
+enum_dispatch!(
+    pub trait Render {
+        fn render(&self, canvas: &dyn Canvas) -> String;
+        fn render_scoped<'a>(&self, canvas: &(dyn Canvas + 'a)) -> String;
+        fn render_shared(&self, canvas: &mut (dyn Canvas + Send + Sync)) -> String;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+pub struct Square;
+
+impl Render for Square {
+    fn render(&self, canvas: &dyn Canvas) -> String {
+        canvas.draw("square")
+    }
+    fn render_scoped<'a>(&self, canvas: &(dyn Canvas + 'a)) -> String {
+        canvas.draw("scoped square")
+    }
+    fn render_shared(&self, canvas: &mut (dyn Canvas + Send + Sync)) -> String {
+        canvas.draw("shared square")
+    }
+}
+
+pub struct Svg;
+
+impl Canvas for Svg {
+    fn draw(&self, name: &str) -> String {
+        format!("<{name}/>")
+    }
+}
+
+#[test]
+fn trait_object_arguments_keep_their_bounds() {
+    let shape = AnyShape::from(Square);
+    assert_eq!(shape.render(&Svg), "<square/>");
+    assert_eq!(shape.render_scoped(&Svg), "<scoped square/>");
+    assert_eq!(shape.render_shared(&mut Svg), "<shared square/>");
+}
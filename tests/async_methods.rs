@@ -0,0 +1,93 @@
+//! Futures of the variants are awaited until they complete, so handlers can wait inside and keep state between calls
+
+use declarative_enum_dispatch::enum_dispatch;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+enum_dispatch!(
+    trait Handler {
+        async fn process(&mut self, input: Vec<u8>, skip: usize) -> usize;
+        async fn finish(self, suffix: &str) -> String
+        where
+            Self: Sized;
+    }
+
+    enum AnyHandler {
+        Counter(Counter),
+        Discard,
+    }
+);
+
+struct Counter(usize);
+struct Discard;
+
+// pending once, so the caller has to poll again
+async fn yield_now() {
+    let mut yielded = false;
+    std::future::poll_fn(|_| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+impl Handler for Counter {
+    async fn process(&mut self, input: Vec<u8>, skip: usize) -> usize {
+        yield_now().await;
+        self.0 += input.len().saturating_sub(skip);
+        self.0
+    }
+    async fn finish(self, suffix: &str) -> String {
+        yield_now().await;
+        format!("{}{suffix}", self.0)
+    }
+}
+
+impl Handler for Discard {
+    async fn process(&mut self, _input: Vec<u8>, _skip: usize) -> usize {
+        0
+    }
+    async fn finish(self, suffix: &str) -> String {
+        suffix.to_string()
+    }
+}
+
+struct Noop;
+
+impl Wake for Noop {
+    fn wake(self: Arc<Self>) {}
+}
+
+fn block_on<F: Future>(future: F) -> (F::Output, usize) {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(Noop));
+    let mut cx = Context::from_waker(&waker);
+    let mut polls = 1;
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return (output, polls);
+        }
+        polls += 1;
+    }
+}
+
+#[test]
+fn pending_futures_are_polled_again() {
+    let mut handler = AnyHandler::from(Counter(0));
+    assert_eq!(block_on(handler.process(vec![1, 2, 3], 1)), (2, 2));
+    assert_eq!(block_on(handler.process(vec![4, 5], 0)), (4, 2));
+    assert_eq!(
+        block_on(handler.finish(" bytes")),
+        ("4 bytes".to_string(), 2)
+    );
+
+    let mut handler = AnyHandler::Discard;
+    assert_eq!(block_on(handler.process(vec![1], 0)), (0, 1));
+    assert_eq!(block_on(handler.finish("none")), ("none".to_string(), 1));
+}
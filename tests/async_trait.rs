@@ -0,0 +1,176 @@
+//! `async fn` of traits with `#[dispatch(async_trait)]` matches impls of the variants made with `#[async_trait]`
+
+use async_trait::async_trait;
+use declarative_enum_dispatch::enum_dispatch;
+use std::future::Future;
+use std::pin::pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+enum_dispatch!(
+    #[dispatch(async_trait)]
+    pub trait Job: Sync {
+        type Id;
+
+        async fn run(&self, n: u32) -> u32;
+        async fn greet(&self, name: &str, suffix: &'static str) -> String;
+        async fn bump(&mut self, by: &u32);
+        async fn into_id(self) -> Self::Id
+        where
+            Self: Sized;
+        async fn describe(&self, mut prefix: String) -> String {
+            prefix.push_str("job");
+            prefix
+        }
+    }
+
+    pub enum AnyJob {
+        Double(Double),
+        Add(Add),
+    }
+
+    with type Id = u32;
+);
+
+pub struct Double;
+pub struct Add(u32);
+
+#[async_trait]
+impl Job for Double {
+    type Id = u32;
+
+    async fn run(&self, n: u32) -> u32 {
+        n * 2
+    }
+    async fn greet(&self, name: &str, suffix: &'static str) -> String {
+        format!("{name}{suffix}")
+    }
+    async fn bump(&mut self, _by: &u32) {}
+    async fn into_id(self) -> u32 {
+        0
+    }
+}
+
+#[async_trait]
+impl Job for Add {
+    type Id = u32;
+
+    async fn run(&self, n: u32) -> u32 {
+        n + self.0
+    }
+    async fn greet(&self, name: &str, suffix: &'static str) -> String {
+        format!("{name}{suffix} {}", self.0)
+    }
+    async fn bump(&mut self, by: &u32) {
+        self.0 += by;
+    }
+    async fn into_id(self) -> u32 {
+        self.0
+    }
+    async fn describe(&self, prefix: String) -> String {
+        format!("{prefix}add {}", self.0)
+    }
+}
+
+enum_dispatch!(
+    #[dispatch(async_trait(?Send))]
+    trait Counter {
+        async fn count(&self) -> usize;
+        async fn total(self) -> usize
+        where
+            Self: Sized,
+        {
+            self.count().await
+        }
+    }
+
+    enum AnyCounter {
+        Shared(Shared),
+    }
+);
+
+struct Shared(Rc<Vec<u8>>);
+
+#[async_trait(?Send)]
+impl Counter for Shared {
+    async fn count(&self) -> usize {
+        self.0.len()
+    }
+}
+
+#[async_trait]
+pub trait Store {
+    async fn get(&self, key: &str) -> Option<u32>;
+}
+
+enum_dispatch!(
+    #[dispatch(async_trait)]
+    use trait Store {
+        async fn get(&self, key: &str) -> Option<u32>;
+    }
+
+    pub enum AnyStore {
+        Empty(Empty),
+    }
+);
+
+pub struct Empty;
+
+#[async_trait]
+impl Store for Empty {
+    async fn get(&self, _key: &str) -> Option<u32> {
+        None
+    }
+}
+
+struct Noop;
+
+impl Wake for Noop {
+    fn wake(self: Arc<Self>) {}
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(Noop));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+fn assert_send<T: Send>(value: T) -> T {
+    value
+}
+
+#[test]
+fn futures_of_variants_are_returned() {
+    let mut job = AnyJob::from(Add(1));
+    assert_eq!(block_on(assert_send(job.run(2))), 3);
+    assert_eq!(block_on(job.greet("hi", "!")), "hi! 1");
+    block_on(job.bump(&2));
+    assert_eq!(block_on(job.describe("> ".to_string())), "> add 3");
+    assert_eq!(block_on(job.into_id()), 3);
+
+    let job = AnyJob::from(Double);
+    assert_eq!(block_on(job.run(2)), 4);
+    assert_eq!(block_on(job.describe(String::new())), "job");
+}
+
+#[test]
+fn enum_is_usable_as_trait_object() {
+    let job: Box<dyn Job<Id = u32>> = Box::new(AnyJob::from(Add(1)));
+    assert_eq!(block_on(job.run(2)), 3);
+
+    let store: Box<dyn Store> = Box::new(AnyStore::from(Empty));
+    assert_eq!(block_on(store.get("key")), None);
+}
+
+#[test]
+fn futures_without_send_are_allowed() {
+    let counter = AnyCounter::from(Shared(Rc::new(vec![1, 2])));
+    assert_eq!(block_on(counter.count()), 2);
+    assert_eq!(block_on(counter.total()), 2);
+}
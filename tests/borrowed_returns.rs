@@ -0,0 +1,70 @@
+//! Returned references keep lifetime elided from the receiver, so they borrow the enum like they borrow the variant value
+
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Named {
+        fn label(&self) -> &str;
+        fn data(&self) -> &[u8];
+        fn inner(&self) -> &String;
+    }
+
+    pub enum AnyNamed {
+        File(File),
+    }
+);
+
+enum_dispatch!(
+    pub trait Buffer {
+        fn buffer_mut(&mut self) -> &mut Vec<u8>;
+        fn last_mut(&mut self) -> Option<&mut u8>;
+    }
+
+    pub enum AnyBuffer {
+        Memory(Memory),
+    }
+);
+
+pub struct File(String);
+
+impl Named for File {
+    fn label(&self) -> &str {
+        &self.0
+    }
+    fn data(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+    fn inner(&self) -> &String {
+        &self.0
+    }
+}
+
+pub struct Memory(Vec<u8>);
+
+impl Buffer for Memory {
+    fn buffer_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.0
+    }
+    fn last_mut(&mut self) -> Option<&mut u8> {
+        self.0.last_mut()
+    }
+}
+
+#[test]
+fn shared_references_are_held_together() {
+    let named = AnyNamed::from(File("log".to_string()));
+    let (label, data, inner) = (named.label(), named.data(), named.inner());
+    assert_eq!(label, "log");
+    assert_eq!(data, b"log");
+    assert_eq!(inner.len(), 3);
+}
+
+#[test]
+fn mutable_references_borrow_the_enum_mutably() {
+    let mut buffer = AnyBuffer::from(Memory(vec![1]));
+    buffer.buffer_mut().push(2);
+    *buffer.last_mut().unwrap() += 1;
+    let bytes = buffer.buffer_mut();
+    bytes.push(4);
+    assert_eq!(bytes, &[1, 3, 4]);
+}
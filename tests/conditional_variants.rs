@@ -0,0 +1,75 @@
+//! Every item generated for a variant disabled with `#[cfg(...)]` is disabled together with it,
+//! so the variant type doesn't have to exist and the matches stay exhaustive
+
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    #[dispatch(as_dyn)]
+    #[dispatch(into_box)]
+    pub trait Area {
+        fn area(&self) -> f32;
+        fn scaled(&self, k: f32) -> Self where Self: Sized;
+    }
+
+    #[dispatch(display)]
+    #[dispatch(partial_eq)]
+    #[dispatch(visitor = ShapeVisitor)]
+    #[dispatch(generate(try_from))]
+    pub enum AnyShape {
+        #[dispatch(default)]
+        Square(Square) as is_square,
+        // `Cube` is never declared
+        #[cfg(any())]
+        Cube(Cube) as is_cube,
+        Dot,
+    }
+);
+
+#[derive(Default, PartialEq)]
+pub struct Square(f32);
+#[derive(PartialEq)]
+pub struct Dot;
+
+impl std::fmt::Display for Square {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "square {}", self.0)
+    }
+}
+
+impl std::fmt::Display for Dot {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "dot")
+    }
+}
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+
+    fn scaled(&self, k: f32) -> Self {
+        Square(self.0 * k)
+    }
+}
+
+impl Area for Dot {
+    fn area(&self) -> f32 {
+        0.0
+    }
+
+    fn scaled(&self, _k: f32) -> Self {
+        Dot
+    }
+}
+
+#[test]
+fn disabled_variant_is_skipped_by_generated_items() {
+    let shape = AnyShape::from(Square(2.0));
+    assert!(shape.is_square());
+    assert_eq!(shape.as_dyn().area(), 4.0);
+    assert!(shape.scaled(0.5) == AnyShape::from(Square(1.0)));
+    assert_eq!(AnyShape::default().to_string(), "square 0");
+    assert_eq!(Square::try_from(shape).ok().unwrap().0, 2.0);
+    let boxed: Box<dyn Area> = AnyShape::Dot.into();
+    assert_eq!(boxed.area(), 0.0);
+}
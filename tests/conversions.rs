@@ -0,0 +1,55 @@
+//! `TryFrom` back to the variant types is generated only with `generate(try_from)`,
+//! so enums conflicting with the blanket `TryFrom` impl of `core` compile by default
+
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Eval {
+        fn eval(&self) -> i64;
+    }
+
+    pub enum Expr {
+        Lit(Lit),
+        Neg(Box<Expr>),
+    }
+
+    #[dispatch(generate(try_from))]
+    pub enum Leaf {
+        Lit(Lit),
+    }
+);
+
+pub struct Lit(i64);
+
+impl Eval for Lit {
+    fn eval(&self) -> i64 {
+        self.0
+    }
+}
+
+impl Eval for Box<Expr> {
+    fn eval(&self) -> i64 {
+        -(**self).eval()
+    }
+}
+
+// conflicts with generated `TryFrom<Expr> for Lit` through the blanket impl
+impl From<Expr> for Lit {
+    fn from(expr: Expr) -> Self {
+        Lit(expr.eval())
+    }
+}
+
+#[test]
+fn recursive_enum_has_no_try_from() {
+    let expr = Expr::from(Box::new(Expr::from(Box::new(Expr::from(Lit(2))))));
+    assert_eq!(expr.eval(), 2);
+    assert_eq!(Expr::from(Box::new(Expr::from(Lit(2)))).eval(), -2);
+    assert_eq!(Lit::from(Expr::from(Box::new(Expr::from(Lit(3))))).0, -3);
+}
+
+#[test]
+fn try_from_is_generated_on_request() {
+    let leaf = Leaf::from(Lit(4));
+    assert_eq!(Lit::try_from(leaf).ok().map(|lit| lit.0), Some(4));
+}
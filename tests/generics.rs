@@ -0,0 +1,107 @@
+//! Parameters of a generic trait bound with `with trait` to parameters of the enum or to concrete types
+
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Count<T> {
+        fn count(&self, item: &T) -> usize;
+    }
+    with trait Count<T>;
+
+    pub enum Container<T: PartialEq> {
+        Vec(Vec<T>),
+        Option(Option<T>),
+    }
+);
+
+impl<T: PartialEq> Count<T> for Vec<T> {
+    fn count(&self, item: &T) -> usize {
+        self.iter().filter(|value| *value == item).count()
+    }
+}
+
+impl<T: PartialEq> Count<T> for Option<T> {
+    fn count(&self, item: &T) -> usize {
+        self.iter().filter(|value| *value == item).count()
+    }
+}
+
+enum_dispatch!(
+    #[dispatch(as_dyn = as_convert)]
+    pub trait Convert<In, Out> {
+        fn convert(&self, value: In) -> Out;
+    }
+    with trait Convert<In = u32, Out = String>;
+
+    pub enum Converter {
+        Decimal(Decimal),
+        Hex(Hex),
+    }
+);
+
+pub struct Decimal;
+pub struct Hex;
+
+// only this instance of the trait is implemented by the variants
+impl Convert<u32, String> for Decimal {
+    fn convert(&self, value: u32) -> String {
+        value.to_string()
+    }
+}
+
+impl Convert<u32, String> for Hex {
+    fn convert(&self, value: u32) -> String {
+        format!("{value:x}")
+    }
+}
+
+enum_dispatch!(
+    pub trait Scale<T> {
+        fn scale(&self, factor: T) -> T;
+    }
+    with trait Scale<T>;
+
+    pub trait Describe<D> {
+        fn describe(&self) -> D;
+    }
+    with trait Describe<D = &'static str>;
+
+    pub enum Number<T: Copy + std::ops::Mul<Output = T>> {
+        Value(Value<T>),
+    }
+);
+
+pub struct Value<T>(T);
+
+impl<T: Copy + std::ops::Mul<Output = T>> Scale<T> for Value<T> {
+    fn scale(&self, factor: T) -> T {
+        self.0 * factor
+    }
+}
+
+impl<T> Describe<&'static str> for Value<T> {
+    fn describe(&self) -> &'static str {
+        "value"
+    }
+}
+
+#[test]
+fn trait_parameter_is_enum_parameter() {
+    assert_eq!(Container::from(vec![1, 2, 1]).count(&1), 2);
+    assert_eq!(Container::from(Some('a')).count(&'a'), 1);
+    assert_eq!(Container::<char>::from(None).count(&'a'), 0);
+}
+
+#[test]
+fn trait_parameters_are_concrete() {
+    assert_eq!(Converter::from(Decimal).convert(255), "255");
+    assert_eq!(Converter::from(Hex).convert(255), "ff");
+    assert_eq!(Converter::from(Hex).as_convert().convert(16), "10");
+}
+
+#[test]
+fn bound_traits_are_combined() {
+    let number = Number::from(Value(3u8));
+    assert_eq!(number.scale(2), 6);
+    assert_eq!(number.describe(), "value");
+}
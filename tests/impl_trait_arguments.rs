@@ -0,0 +1,174 @@
+//! `impl Trait` arguments are anonymous type parameters of the method, they are forwarded as is and left to inference
+
+use declarative_enum_dispatch::enum_dispatch;
+use std::fmt::Write;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> i32;
+        fn covers(&self, a: &impl Area, b: &impl Area) -> bool;
+        fn total(
+            &self,
+            a: impl Into<i32>,
+            b: &impl Area,
+            rest: impl IntoIterator<Item = i32>,
+        ) -> i32;
+        fn scaled<S: Copy + Into<i32>>(&self, scale: S, offset: impl Into<i32>) -> i32;
+    }
+
+    pub enum AnyShape {
+        Square(Square),
+    }
+);
+
+enum_dispatch!(
+    pub trait Numbers {
+        fn map(&self, f: impl Fn(i32) -> i32) -> i32;
+        fn visit(&mut self, f: impl FnMut(&mut i32));
+        fn zip(&self, f: impl Fn(i32, i32) -> (i32, i32), g: impl FnOnce() -> i32) -> i32;
+    }
+
+    pub enum AnyNumbers {
+        List(List),
+    }
+);
+
+pub trait Weight {
+    fn weight(&self) -> u32;
+}
+
+impl Weight for u32 {
+    fn weight(&self) -> u32 {
+        *self
+    }
+}
+
+impl Weight for &str {
+    fn weight(&self) -> u32 {
+        self.len() as u32
+    }
+}
+
+enum_dispatch!(
+    pub trait Scale {
+        fn batch(&self, items: &[impl Weight]) -> u32;
+        fn owned(&mut self, items: Vec<impl Weight>) -> u32;
+        fn mixed(&self, items: &[Box<dyn Weight>]) -> u32;
+    }
+
+    pub enum AnyScale {
+        Total(Total),
+    }
+);
+
+pub trait Context {
+    fn indent(&self) -> usize;
+}
+
+pub struct Nested(usize);
+
+impl Context for Nested {
+    fn indent(&self) -> usize {
+        self.0
+    }
+}
+
+enum_dispatch!(
+    pub trait Render {
+        fn render_into<W: Write>(&self, ctx: &impl Context, out: &mut W);
+    }
+
+    pub enum AnyWidget {
+        Label(Label),
+    }
+);
+
+pub struct Square(i32);
+pub struct List(Vec<i32>);
+pub struct Total(u32);
+pub struct Label(&'static str);
+
+impl Area for Square {
+    fn area(&self) -> i32 {
+        self.0 * self.0
+    }
+    fn covers(&self, a: &impl Area, b: &impl Area) -> bool {
+        self.area() >= a.area() + b.area()
+    }
+    fn total(&self, a: impl Into<i32>, b: &impl Area, rest: impl IntoIterator<Item = i32>) -> i32 {
+        self.area() + a.into() + b.area() + rest.into_iter().sum::<i32>()
+    }
+    fn scaled<S: Copy + Into<i32>>(&self, scale: S, offset: impl Into<i32>) -> i32 {
+        self.area() * scale.into() + offset.into()
+    }
+}
+
+impl Numbers for List {
+    fn map(&self, f: impl Fn(i32) -> i32) -> i32 {
+        self.0.iter().map(|x| f(*x)).sum()
+    }
+    fn visit(&mut self, f: impl FnMut(&mut i32)) {
+        self.0.iter_mut().for_each(f);
+    }
+    fn zip(&self, f: impl Fn(i32, i32) -> (i32, i32), g: impl FnOnce() -> i32) -> i32 {
+        let (a, b) = f(self.0[0], self.0[1]);
+        a + b + g()
+    }
+}
+
+impl Scale for Total {
+    fn batch(&self, items: &[impl Weight]) -> u32 {
+        self.0 + items.iter().map(Weight::weight).sum::<u32>()
+    }
+    fn owned(&mut self, items: Vec<impl Weight>) -> u32 {
+        self.0 += items.iter().map(Weight::weight).sum::<u32>();
+        self.0
+    }
+    fn mixed(&self, items: &[Box<dyn Weight>]) -> u32 {
+        self.0 + items.iter().map(|item| item.weight()).sum::<u32>()
+    }
+}
+
+impl Render for Label {
+    fn render_into<W: Write>(&self, ctx: &impl Context, out: &mut W) {
+        write!(out, "{:indent$}{}", "", self.0, indent = ctx.indent()).unwrap();
+    }
+}
+
+#[test]
+fn several_impl_trait_arguments_are_forwarded() {
+    let shape = AnyShape::from(Square(3));
+    assert!(shape.covers(&Square(2), &AnyShape::from(Square(2))));
+    assert_eq!(shape.total(1u8, &Square(1), [2, 3]), 16);
+    assert_eq!(shape.scaled(2u8, 1i8), 19);
+}
+
+#[test]
+fn closure_types_are_captured_whole() {
+    let mut numbers = AnyNumbers::from(List(vec![1, 2, 3]));
+    assert_eq!(numbers.map(|x| x * 2), 12);
+    let mut visited = 0;
+    numbers.visit(|x| {
+        *x += 1;
+        visited += 1;
+    });
+    assert_eq!(visited, 3);
+    assert_eq!(numbers.zip(|a, b| (b, a), || 10), 15);
+}
+
+#[test]
+fn nested_impl_trait_arguments_are_forwarded() {
+    let mut scale = AnyScale::from(Total(1));
+    assert_eq!(scale.batch(&[1u32, 2]), 4);
+    assert_eq!(scale.owned(vec!["ab", "c"]), 4);
+    assert_eq!(scale.mixed(&[Box::new(1u32), Box::new("abc")]), 8);
+}
+
+#[test]
+fn named_parameters_are_forwarded_with_impl_trait_arguments() {
+    let widget = AnyWidget::from(Label("title"));
+    let mut out = String::new();
+    widget.render_into(&Nested(2), &mut out);
+    widget.render_into::<String>(&Nested(0), &mut out);
+    assert_eq!(out, "  titletitle");
+}
@@ -0,0 +1,101 @@
+//! Methods are built several per step, so a trait with hundreds of them
+//! has to fit into the default `recursion_limit`
+
+use declarative_enum_dispatch::enum_dispatch;
+
+macro_rules! registers {
+    ($($register:ident)*) => {
+        enum_dispatch!(
+            pub trait Registers {
+                /// Register 0
+                fn r0(&self) -> u32;
+                $(
+                    #[cfg(all())]
+                    fn $register(&self) -> u32;
+                )*
+            }
+
+            pub enum AnyRegisters {
+                Cpu(Cpu),
+            }
+        );
+
+        impl Registers for Cpu {
+            fn r0(&self) -> u32 {
+                0
+            }
+            $(
+                fn $register(&self) -> u32 {
+                    1
+                }
+            )*
+        }
+    };
+}
+
+// attributes other than doc and `cfg`, arguments and borrowed return values
+macro_rules! labels {
+    ($($label:ident)*) => {
+        enum_dispatch!(
+            pub trait Labels {
+                $(
+                    #[must_use]
+                    fn $label(&self, index: usize) -> &str;
+                )*
+                #[must_use]
+                fn nth(&self, index: usize) -> Option<&str>;
+            }
+
+            pub enum AnyLabels {
+                Cpu(Cpu),
+            }
+        );
+
+        impl Labels for Cpu {
+            $(
+                fn $label(&self, index: usize) -> &str {
+                    &stringify!($label)[..index]
+                }
+            )*
+            fn nth(&self, index: usize) -> Option<&str> {
+                ["first", "second"].get(index).copied()
+            }
+        }
+    };
+}
+
+pub struct Cpu;
+
+labels!(
+    l1 l2 l3 l4 l5 l6 l7 l8 l9 l10 l11 l12 l13 l14 l15 l16 l17 l18 l19 l20 l21 l22 l23 l24 l25 l26 l27 l28 l29 l30
+    l31 l32 l33 l34 l35 l36 l37 l38 l39 l40 l41 l42 l43 l44 l45 l46 l47 l48 l49 l50 l51 l52 l53 l54 l55 l56 l57 l58 l59 l60
+    l61 l62 l63 l64 l65 l66 l67 l68 l69 l70 l71 l72 l73 l74 l75 l76 l77 l78 l79 l80 l81 l82 l83 l84 l85 l86 l87 l88 l89 l90
+    l91 l92 l93 l94 l95 l96 l97 l98 l99 l100 l101 l102 l103 l104 l105 l106 l107 l108 l109 l110 l111 l112 l113 l114 l115 l116 l117 l118 l119 l120
+    l121 l122 l123 l124 l125 l126 l127 l128 l129 l130 l131 l132 l133 l134 l135 l136 l137 l138 l139 l140 l141 l142 l143 l144 l145 l146 l147 l148 l149 l150
+);
+
+registers!(
+    r1 r2 r3 r4 r5 r6 r7 r8 r9 r10 r11 r12 r13 r14 r15 r16 r17 r18 r19 r20 r21 r22 r23 r24 r25 r26 r27 r28 r29 r30
+    r31 r32 r33 r34 r35 r36 r37 r38 r39 r40 r41 r42 r43 r44 r45 r46 r47 r48 r49 r50 r51 r52 r53 r54 r55 r56 r57 r58 r59 r60
+    r61 r62 r63 r64 r65 r66 r67 r68 r69 r70 r71 r72 r73 r74 r75 r76 r77 r78 r79 r80 r81 r82 r83 r84 r85 r86 r87 r88 r89 r90
+    r91 r92 r93 r94 r95 r96 r97 r98 r99 r100 r101 r102 r103 r104 r105 r106 r107 r108 r109 r110 r111 r112 r113 r114 r115 r116 r117 r118 r119 r120
+    r121 r122 r123 r124 r125 r126 r127 r128 r129 r130 r131 r132 r133 r134 r135 r136 r137 r138 r139 r140 r141 r142 r143 r144 r145 r146 r147 r148 r149 r150
+    r151 r152 r153 r154 r155 r156 r157 r158 r159 r160 r161 r162 r163 r164 r165 r166 r167 r168 r169 r170 r171 r172 r173 r174 r175 r176 r177 r178 r179 r180
+    r181 r182 r183 r184 r185 r186 r187 r188 r189 r190 r191 r192 r193 r194 r195 r196 r197 r198 r199
+);
+
+#[test]
+fn all_methods_are_dispatched() {
+    let registers = AnyRegisters::from(Cpu);
+    assert_eq!(registers.r0(), 0);
+    assert_eq!(registers.r100(), 1);
+    assert_eq!(registers.r199(), 1);
+}
+
+#[test]
+fn methods_with_arguments_and_attributes_are_dispatched() {
+    let labels = AnyLabels::from(Cpu);
+    assert_eq!(labels.l1(1), "l");
+    assert_eq!(labels.l150(3), "l15");
+    assert_eq!(labels.nth(1), Some("second"));
+}
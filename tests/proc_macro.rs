@@ -0,0 +1,199 @@
+//! `#[enum_dispatch]` attribute macro generates the same dispatch from the trait and the enum parsed with `syn`
+
+#![cfg(feature = "proc-macro")]
+
+use declarative_enum_dispatch::attributes::enum_dispatch;
+
+mod shapes {
+    use super::enum_dispatch;
+
+    #[enum_dispatch]
+    pub trait Area {
+        fn area(&self) -> f32;
+        fn scale(&mut self, k: f32);
+        fn grown(&self, by: f32) -> Self
+        where
+            Self: Sized;
+        fn into_name(self) -> String
+        where
+            Self: Sized;
+        fn describe(&self, prefix: &str) -> String {
+            format!("{prefix}{}", self.area())
+        }
+        fn unit() -> f32
+        where
+            Self: Sized,
+        {
+            1.0
+        }
+    }
+}
+
+use shapes::Area;
+
+#[enum_dispatch(shapes::Area)]
+#[derive(Debug, PartialEq)]
+pub enum AnyShape {
+    Square(Square),
+    Circle(Circle),
+    #[cfg(any())]
+    Cube(Cube),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Square(f32);
+#[derive(Debug, PartialEq)]
+pub struct Circle(f32);
+
+impl Area for Square {
+    fn area(&self) -> f32 {
+        self.0 * self.0
+    }
+    fn scale(&mut self, k: f32) {
+        self.0 *= k;
+    }
+    fn grown(&self, by: f32) -> Self {
+        Square(self.0 + by)
+    }
+    fn into_name(self) -> String {
+        "square".to_string()
+    }
+}
+
+impl Area for Circle {
+    fn area(&self) -> f32 {
+        3.0 * self.0 * self.0
+    }
+    fn scale(&mut self, k: f32) {
+        self.0 *= k;
+    }
+    fn grown(&self, by: f32) -> Self {
+        Circle(self.0 + by)
+    }
+    fn into_name(self) -> String {
+        "circle".to_string()
+    }
+    fn describe(&self, _prefix: &str) -> String {
+        "round".to_string()
+    }
+}
+
+#[enum_dispatch]
+pub trait Container<T> {
+    type Item;
+
+    fn get(&self, index: usize) -> Option<&T>;
+    fn first_item(&self) -> Option<Self::Item>;
+    fn find<P: Fn(&T) -> bool>(&self, predicate: P) -> Option<usize>;
+    fn count(&self, value: impl PartialEq<T>) -> usize;
+}
+
+#[enum_dispatch(Container)]
+pub enum AnyContainer<T: Clone> {
+    List(Vec<T>),
+    One(One<T>),
+}
+
+pub struct One<T>(T);
+
+impl<T: Clone> Container<T> for Vec<T> {
+    type Item = T;
+
+    fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+    fn first_item(&self) -> Option<T> {
+        self.first().cloned()
+    }
+    fn find<P: Fn(&T) -> bool>(&self, predicate: P) -> Option<usize> {
+        self.iter().position(predicate)
+    }
+    fn count(&self, value: impl PartialEq<T>) -> usize {
+        self.iter().filter(|item| value == **item).count()
+    }
+}
+
+impl<T: Clone> Container<T> for One<T> {
+    type Item = T;
+
+    fn get(&self, index: usize) -> Option<&T> {
+        (index == 0).then_some(&self.0)
+    }
+    fn first_item(&self) -> Option<T> {
+        Some(self.0.clone())
+    }
+    fn find<P: Fn(&T) -> bool>(&self, predicate: P) -> Option<usize> {
+        predicate(&self.0).then_some(0)
+    }
+    fn count(&self, value: impl PartialEq<T>) -> usize {
+        usize::from(value == self.0)
+    }
+}
+
+#[enum_dispatch]
+trait Job {
+    async fn run(&mut self, n: u32) -> u32;
+    unsafe fn raw(&self) -> u32;
+}
+
+#[enum_dispatch(Job)]
+enum AnyJob {
+    Double(Double),
+}
+
+struct Double(u32);
+
+impl Job for Double {
+    async fn run(&mut self, n: u32) -> u32 {
+        self.0 += n;
+        self.0 * 2
+    }
+    unsafe fn raw(&self) -> u32 {
+        self.0
+    }
+}
+
+#[test]
+fn methods_are_dispatched() {
+    let mut shape = AnyShape::from(Square(2.0));
+    assert_eq!(shape.area(), 4.0);
+    shape.scale(2.0);
+    assert_eq!(shape.grown(1.0), AnyShape::Square(Square(5.0)));
+    assert_eq!(shape.describe("area "), "area 16");
+    assert_eq!(AnyShape::from(Circle(1.0)).describe("area "), "round");
+    assert_eq!(AnyShape::unit(), 1.0);
+    assert_eq!(AnyShape::from(Circle(1.0)).into_name(), "circle");
+}
+
+#[test]
+fn generic_traits_and_enums_are_dispatched() {
+    let list = AnyContainer::from(vec![1, 2, 2]);
+    assert_eq!(list.get(1), Some(&2));
+    assert_eq!(list.first_item(), Some(1));
+    assert_eq!(list.find(|item| *item == 2), Some(1));
+    assert_eq!(list.count(2), 2);
+
+    let one = AnyContainer::from(One("a"));
+    assert_eq!(one.get(0), Some(&"a"));
+    assert_eq!(one.find(|item| item.is_empty()), None);
+}
+
+#[test]
+fn async_and_unsafe_methods_are_dispatched() {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct Noop;
+
+    impl Wake for Noop {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let mut job = AnyJob::from(Double(1));
+    let waker = Waker::from(Arc::new(Noop));
+    let run = pin!(job.run(2)).poll(&mut Context::from_waker(&waker));
+    assert_eq!(run, Poll::Ready(6));
+    assert_eq!(unsafe { job.raw() }, 3);
+}
@@ -0,0 +1,68 @@
+//! `#[repr(...)]` of the enum is kept as is, so enums of unit variants keep their layout
+
+use declarative_enum_dispatch::enum_dispatch;
+
+enum_dispatch!(
+    pub trait Level {
+        fn name(&self) -> &'static str;
+    }
+
+    #[repr(u8)]
+    #[derive(Clone, Copy)]
+    pub enum LogLevel {
+        Info = 1,
+        Warn,
+    }
+);
+
+enum_dispatch!(
+    pub trait Port {
+        fn number(&self) -> u16;
+    }
+
+    #[repr(u16)]
+    pub enum WidePort {
+        Http = 80,
+        Https = 443,
+    }
+);
+
+pub struct Info;
+pub struct Warn;
+pub struct Http;
+pub struct Https;
+
+impl Level for Info {
+    fn name(&self) -> &'static str {
+        "info"
+    }
+}
+
+impl Level for Warn {
+    fn name(&self) -> &'static str {
+        "warn"
+    }
+}
+
+impl Port for Http {
+    fn number(&self) -> u16 {
+        80
+    }
+}
+
+impl Port for Https {
+    fn number(&self) -> u16 {
+        443
+    }
+}
+
+#[test]
+fn repr_of_enum_is_kept() {
+    assert_eq!(std::mem::size_of::<LogLevel>(), 1);
+    assert_eq!(std::mem::size_of::<WidePort>(), 2);
+    assert_eq!(LogLevel::Warn as u8, 2);
+    // valid because of `#[repr(u8)]`
+    let level: LogLevel = unsafe { std::mem::transmute(2u8) };
+    assert_eq!(level.name(), "warn");
+    assert_eq!(WidePort::Https as u16, WidePort::Https.number());
+}
@@ -0,0 +1,61 @@
+//! Return types other than a simple path are collected token by token, so they are returned as is
+
+use declarative_enum_dispatch::enum_dispatch;
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq)]
+pub struct Point(i32, i32);
+
+enum_dispatch!(
+    pub trait Shape {
+        fn bounds(&self) -> (Point, Point);
+        fn grouped(&self) -> HashMap<String, Vec<i32>>;
+        fn nested(&self) -> Option<Vec<Vec<Option<i32>>>>;
+        fn pixels(&self) -> ((u8,), [Option<Vec<u8>>; 2]);
+        fn rows<T>(&self) -> Vec<Vec<T>>
+        where
+            T: Default;
+        fn scale(&self) -> Box<dyn Fn(i32) -> Vec<Vec<i32>>>;
+    }
+
+    pub enum AnyShape {
+        Rect(Rect),
+    }
+);
+
+pub struct Rect;
+
+impl Shape for Rect {
+    fn bounds(&self) -> (Point, Point) {
+        (Point(0, 0), Point(1, 1))
+    }
+    fn grouped(&self) -> HashMap<String, Vec<i32>> {
+        HashMap::from([("corners".to_string(), vec![4])])
+    }
+    fn nested(&self) -> Option<Vec<Vec<Option<i32>>>> {
+        Some(vec![vec![Some(1), None]])
+    }
+    fn pixels(&self) -> ((u8,), [Option<Vec<u8>>; 2]) {
+        ((1,), [None, Some(vec![0])])
+    }
+    fn rows<T>(&self) -> Vec<Vec<T>>
+    where
+        T: Default,
+    {
+        vec![vec![T::default()]]
+    }
+    fn scale(&self) -> Box<dyn Fn(i32) -> Vec<Vec<i32>>> {
+        Box::new(|x| vec![vec![x, x]])
+    }
+}
+
+#[test]
+fn compound_return_types_are_returned_as_is() {
+    let shape = AnyShape::from(Rect);
+    assert_eq!(shape.bounds(), (Point(0, 0), Point(1, 1)));
+    assert_eq!(shape.grouped()["corners"], [4]);
+    assert_eq!(shape.nested(), Some(vec![vec![Some(1), None]]));
+    assert_eq!(shape.pixels(), ((1,), [None, Some(vec![0])]));
+    assert_eq!(shape.rows::<u8>(), [[0]]);
+    assert_eq!(shape.scale()(2), [[2, 2]]);
+}
@@ -0,0 +1,63 @@
+//! Variant types can be paths, so types with the same name from different modules are dispatched and converted as usual
+
+use db::Connection;
+use declarative_enum_dispatch::enum_dispatch;
+
+pub mod db {
+    pub trait Connection {
+        fn url(&self) -> String;
+    }
+
+    pub mod postgres {
+        pub struct Conn(pub u16);
+
+        impl super::Connection for Conn {
+            fn url(&self) -> String {
+                format!("postgres://localhost:{}", self.0)
+            }
+        }
+    }
+
+    pub mod sqlite {
+        pub struct Conn(pub &'static str);
+
+        impl super::Connection for Conn {
+            fn url(&self) -> String {
+                format!("sqlite://{}", self.0)
+            }
+        }
+    }
+}
+
+enum_dispatch!(
+    use trait db::Connection {
+        fn url(&self) -> String;
+    }
+
+    #[dispatch(generate(try_from, as_ref))]
+    pub enum Backend {
+        Postgres(db::postgres::Conn),
+        Sqlite(::std::boxed::Box<db::sqlite::Conn>),
+    }
+);
+
+impl db::Connection for Box<db::sqlite::Conn> {
+    fn url(&self) -> String {
+        (**self).url()
+    }
+}
+
+#[test]
+fn variant_types_are_paths() {
+    assert_eq!(
+        Backend::from(db::postgres::Conn(5432)).url(),
+        "postgres://localhost:5432"
+    );
+    let sqlite = Backend::from(Box::new(db::sqlite::Conn("app.db")));
+    assert_eq!(sqlite.url(), "sqlite://app.db");
+    assert!(sqlite.as_variant::<db::postgres::Conn>().is_none());
+    assert_eq!(
+        <Box<db::sqlite::Conn>>::try_from(sqlite).ok().unwrap().0,
+        "app.db"
+    );
+}
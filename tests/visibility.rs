@@ -0,0 +1,72 @@
+//! Trait and enum keep their own visibility, generated impls have none and accessors of the enum use visibility of the enum,
+//! so any combination of them compiles without warnings
+
+#![deny(warnings)]
+
+macro_rules! shapes {
+    ($($module:ident: $trait_vis:vis trait, $enum_vis:vis enum;)+) => {
+        $(
+            pub mod $module {
+                use declarative_enum_dispatch::enum_dispatch;
+
+                enum_dispatch!(
+                    #[dispatch(as_dyn)]
+                    #[dispatch(into_box)]
+                    $trait_vis trait Area {
+                        fn area(&self) -> i32;
+                    }
+
+                    #[dispatch(partial_eq)]
+                    #[dispatch(generate(variant_names))]
+                    $enum_vis enum AnyShape {
+                        Square(Square),
+                        Dot,
+                    }
+                );
+
+                #[derive(PartialEq)]
+                pub struct Square(pub i32);
+                #[derive(PartialEq)]
+                pub struct Dot;
+
+                impl Area for Square {
+                    fn area(&self) -> i32 {
+                        self.0 * self.0
+                    }
+                }
+                impl Area for Dot {
+                    fn area(&self) -> i32 {
+                        0
+                    }
+                }
+
+                pub fn check() {
+                    let shape = AnyShape::from(Square(2));
+                    assert_eq!(shape.area(), 4);
+                    assert_eq!(shape.as_dyn().area(), 4);
+                    assert!(shape == AnyShape::from(Square(2)));
+                    assert_eq!(AnyShape::VARIANTS, &["Square", "Dot"]);
+                    let boxed: Box<dyn Area> = shape.into();
+                    assert_eq!(boxed.area(), 4);
+                }
+            }
+        )+
+
+        #[test]
+        fn every_visibility_compiles_without_warnings() {
+            $($module::check();)+
+        }
+    };
+}
+
+shapes!(
+    private_private: trait, enum;
+    private_crate: trait, pub(crate) enum;
+    private_public: trait, pub enum;
+    crate_public: pub(crate) trait, pub enum;
+    super_public: pub(super) trait, pub enum;
+    public_private: pub trait, enum;
+    public_crate: pub trait, pub(crate) enum;
+    public_super: pub trait, pub(super) enum;
+    public_public: pub trait, pub enum;
+);
@@ -0,0 +1,107 @@
+//! Methods of a single dispatched trait are called with method syntax,
+//! so variants wrapping a type implementing the trait are dereferenced to it
+
+use declarative_enum_dispatch::enum_dispatch;
+use std::rc::Rc;
+use std::sync::Arc;
+
+enum_dispatch!(
+    pub trait Area {
+        fn area(&self) -> u32;
+        fn scale(&mut self, factor: u32);
+    }
+
+    pub enum Shape {
+        Rect(Rect),
+        Cached(Arc<Rect>),
+        Boxed(Box<Square>),
+        Shared(Rc<Square>),
+    }
+);
+
+#[derive(Clone)]
+pub struct Rect(u32, u32);
+pub struct Square(u32);
+
+impl Area for Rect {
+    fn area(&self) -> u32 {
+        self.0 * self.1
+    }
+    fn scale(&mut self, factor: u32) {
+        self.0 *= factor;
+        self.1 *= factor;
+    }
+}
+
+impl Area for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+    fn scale(&mut self, factor: u32) {
+        self.0 *= factor;
+    }
+}
+
+// shared values can't be scaled, `DerefMut` is not implemented for `Arc` and `Rc`
+impl Area for Arc<Rect> {
+    fn area(&self) -> u32 {
+        (**self).area()
+    }
+    fn scale(&mut self, factor: u32) {
+        Arc::make_mut(self).scale(factor);
+    }
+}
+
+impl Area for Rc<Square> {
+    fn area(&self) -> u32 {
+        (**self).area()
+    }
+    fn scale(&mut self, _factor: u32) {}
+}
+
+enum_dispatch!(
+    pub trait Perimeter {
+        fn perimeter(&self) -> u32;
+    }
+
+    pub enum Outline<'a> {
+        Borrowed(&'a Rect),
+        Boxed(Box<Rect>),
+        Counted(Rc<Rect>),
+    }
+);
+
+impl Perimeter for Rect {
+    fn perimeter(&self) -> u32 {
+        2 * (self.0 + self.1)
+    }
+}
+
+#[test]
+fn wrapped_variants_are_dereferenced() {
+    let rect = Rect(2, 3);
+    assert_eq!(Outline::from(&rect).perimeter(), 10);
+    assert_eq!(Outline::from(Box::new(Rect(1, 1))).perimeter(), 4);
+    assert_eq!(Outline::from(Rc::new(Rect(1, 2))).perimeter(), 6);
+}
+
+#[test]
+fn box_is_dereferenced_mutably() {
+    let mut shape = Shape::from(Box::new(Square(2)));
+    shape.scale(3);
+    assert_eq!(shape.area(), 36);
+}
+
+#[test]
+fn wrapper_implementing_the_trait_is_called_directly() {
+    let rect = Arc::new(Rect(2, 3));
+    let mut shape = Shape::from(Arc::clone(&rect));
+    shape.scale(2);
+    assert_eq!(shape.area(), 24);
+    assert_eq!(rect.area(), 6);
+
+    let mut shape = Shape::from(Rc::new(Square(2)));
+    shape.scale(2);
+    assert_eq!(shape.area(), 4);
+    assert_eq!(Shape::from(Rect(1, 2)).area(), 2);
+}